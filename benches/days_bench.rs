@@ -1,32 +1,55 @@
-use aoc_2022::DAYS;
+use aoc_2022::{input, Parts, YEARS};
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
-use std::fs;
 
 pub fn full_bench(c: &mut Criterion) {
-    let mut days_inputs = Vec::with_capacity(25);
-    for (i, f) in DAYS.iter().copied().enumerate() {
-        let day = i + 1;
-        if let Some(f) = f {
-            let input = fs::read_to_string(format!("input/2022/day{day}.txt")).unwrap();
-            days_inputs.push((day, f, input));
+    let mut days_inputs = Vec::with_capacity(25 * YEARS.len());
+    for year in YEARS {
+        for (day, f) in year.implemented_days() {
+            let generator = year.generator(day).unwrap();
+            let path = input::day_path(year.year, day, false);
+            match input::read_input(&path) {
+                Ok(input) => days_inputs.push((year.year, day, f, generator, input)),
+                Err(e) => eprintln!("Skipping {}, unable to read it: {e}", path.display()),
+            }
         }
     }
     c.bench_function("all_days", |b| {
         b.iter(|| {
-            for (_day, f, input) in &days_inputs {
-                f(black_box(input), false);
+            for (_year, _day, f, _generator, input) in &days_inputs {
+                black_box(f(black_box(input), Parts::Both));
             }
         })
     });
     let mut group = c.benchmark_group("days");
-    for (day, f, input) in &days_inputs {
-        group.bench_function(&day.to_string(), |b| {
+    for (year, day, f, _generator, input) in &days_inputs {
+        group.bench_function(format!("{year}/{day}"), |b| {
             b.iter(|| {
-                f(black_box(input), false);
+                black_box(f(black_box(input), Parts::Both));
             })
         });
     }
     group.finish();
+
+    for (year, day, _f, generator, input) in &days_inputs {
+        // Only generate once, the parts are timed on their own
+        let generated = match generator(input) {
+            Ok(generated) => generated,
+            Err(e) => {
+                let path = input::day_path(*year, *day, false);
+                eprintln!("Skipping {}:{e}", path.display());
+                continue;
+            }
+        };
+        let mut group = c.benchmark_group(format!("{year}/day{day}"));
+        group.bench_function("generator", |b| {
+            b.iter_with_large_drop(|| generator(black_box(input)))
+        });
+        group.bench_function("part_1", |b| b.iter(|| generated.part_1()));
+        if generated.has_part_2() {
+            group.bench_function("part_2", |b| b.iter(|| generated.part_2()));
+        }
+        group.finish();
+    }
 }
 
 criterion_group!(benches, full_bench);
@@ -0,0 +1,154 @@
+//! Generates the registry of days, from the `dayN.rs` files in `src/` (2022) and `src/yYYYY/`
+//!
+//! The output is included by `lib.rs`. Each day's module is preceded by a `day_input!` macro,
+//! which `day_test!` uses to embed the demo input, and to ignore tests of missing inputs.
+
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+use std::{env, fs};
+
+/// The year of days at the root of `src/`
+const ROOT_YEAR: u32 = 2022;
+/// The key file of encrypted inputs when `AOC_INPUT_KEY_FILE` isn't set, see `crypt.rs`
+const DEFAULT_KEY_FILE: &str = ".input_key";
+
+fn main() {
+    let manifest_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+    let src = manifest_dir.join("src");
+    // These scan the whole directory, so adding a day (or year), or an input, re-runs this
+    println!("cargo:rerun-if-changed=src");
+    println!("cargo:rerun-if-changed=input");
+    // Tests of encrypted inputs are ignored without a key to decrypt them
+    println!("cargo:rerun-if-env-changed=AOC_INPUT_KEY");
+    println!("cargo:rerun-if-env-changed=AOC_INPUT_KEY_FILE");
+    let key_file = env::var_os("AOC_INPUT_KEY_FILE")
+        .map_or_else(|| manifest_dir.join(DEFAULT_KEY_FILE), PathBuf::from);
+    // Watching a missing file would re-run this on every build, so a new key file is only noticed
+    // once something else changes, e.g. the inputs encrypted with it
+    if key_file.exists() {
+        println!("cargo:rerun-if-changed={}", key_file.display());
+    }
+    let has_key = env::var_os("AOC_INPUT_KEY").is_some() || key_file.exists();
+
+    let mut years = vec![(ROOT_YEAR, find_days(&src))];
+    for entry in fs::read_dir(&src).unwrap() {
+        let path = entry.unwrap().path();
+        let year = path
+            .file_name()
+            .and_then(|name| name.to_str()?.strip_prefix('y')?.parse::<u32>().ok());
+        if let (Some(year), true) = (year, path.is_dir()) {
+            years.push((year, find_days(&path)));
+        }
+    }
+    years.sort_unstable_by_key(|&(year, _)| year);
+
+    let mut out = String::from("// Generated by build.rs, from the day modules in src/\n");
+    let mut registry = String::new();
+    for (year, days) in &years {
+        let mut modules = String::new();
+        for (day, path) in days {
+            modules += &day_input_macro(&manifest_dir, has_key, *year, *day);
+            let path = path.to_str().expect("non UTF-8 path to a day");
+            writeln!(modules, "#[path = {path:?}]\npub mod day{day};").unwrap();
+        }
+        let names: Vec<_> = days.iter().map(|(day, _)| format!("day{day}")).collect();
+        if *year == ROOT_YEAR {
+            writeln!(out, "{modules}\ndays![{}];", names.join(", ")).unwrap();
+            writeln!(registry, "    {year} => self,").unwrap();
+        } else {
+            let modules = modules.trim_start().replace('\n', "\n    ");
+            writeln!(
+                out,
+                "\npub mod y{year} {{\n    {modules}\n    crate::days![{}];\n}}",
+                names.join(", ")
+            )
+            .unwrap();
+            writeln!(registry, "    {year} => y{year},").unwrap();
+        }
+    }
+    writeln!(out, "\nyears! {{\n{registry}}}").unwrap();
+
+    let out_path = Path::new(&env::var("OUT_DIR").unwrap()).join("days.rs");
+    // Only write if changed, to not needlessly rebuild
+    if fs::read_to_string(&out_path).ok().as_deref() != Some(&*out) {
+        fs::write(out_path, out).unwrap();
+    }
+}
+
+/// The `day_input!` macro for a day, which in the day's module expands:
+///
+/// - `day_input!(demo)` to the demo input, embedded with `include_str!`
+/// - `day_input!(real)` to the path of the real input
+/// - `day_input!(demo_test $test)` and `day_input!(real_test $test)` to `$test`, ignored if the
+///   input is missing or empty, or only encrypted without a key to decrypt it
+fn day_input_macro(manifest_dir: &Path, has_key: bool, year: u32, day: u32) -> String {
+    let demo = format!("input/{year}/demo{day}.txt");
+    let real = format!("input/{year}/day{day}.txt");
+    let in_manifest_dir =
+        |path: &str| format!("concat!(env!(\"CARGO_MANIFEST_DIR\"), \"/{path}\")");
+
+    // Empty inputs are as good as missing, e.g. the demo input of a new day, before it's pasted in
+    let missing = |path: &str| match fs::metadata(manifest_dir.join(path)) {
+        Ok(metadata) if metadata.len() > 0 => None,
+        Ok(_) => Some(format!("{path} is empty")),
+        Err(_) => Some(format!("{path} is missing")),
+    };
+    let demo_ignored = missing(&demo);
+    // Either the plaintext, or encrypted input
+    let real_ignored = if manifest_dir.join(&real).exists()
+        || !manifest_dir.join(format!("{real}.enc")).exists()
+    {
+        missing(&real)
+    } else if !has_key {
+        Some(format!(
+            "{real} is encrypted, and there's no key to decrypt it with \
+                (set AOC_INPUT_KEY or save it in {DEFAULT_KEY_FILE})"
+        ))
+    } else {
+        None
+    };
+    let test = |ignored: &Option<String>| match ignored {
+        Some(reason) => format!("#[ignore = {reason:?}] $test"),
+        None => "$test".to_string(),
+    };
+    let demo_input = match &demo_ignored {
+        Some(reason) => format!("panic!({reason:?})"),
+        None => format!("include_str!({})", in_manifest_dir(&demo)),
+    };
+
+    format!(
+        "\n#[allow(unused_macros)]\nmacro_rules! day_input {{\n    \
+            (demo) => {{ {demo_input} }};\n    \
+            (real) => {{ {} }};\n    \
+            (demo_test $test:item) => {{ {} }};\n    \
+            (real_test $test:item) => {{ {} }};\n\
+        }}\n",
+        in_manifest_dir(&real),
+        test(&demo_ignored),
+        test(&real_ignored),
+    )
+}
+
+/// The `dayN.rs` files directly in `dir`, ordered by day
+fn find_days(dir: &Path) -> Vec<(u32, PathBuf)> {
+    let mut days: Vec<_> = fs::read_dir(dir)
+        .unwrap()
+        .filter_map(|entry| {
+            let path = entry.unwrap().path();
+            let name = path.file_name()?.to_str()?;
+            let day: u32 = name
+                .strip_prefix("day")?
+                .strip_suffix(".rs")?
+                .parse()
+                .ok()?;
+            assert!(
+                (1..=25).contains(&day),
+                "{} isn't a day of advent",
+                path.display()
+            );
+            Some((day, path))
+        })
+        .collect();
+    days.sort_unstable_by_key(|&(day, _)| day);
+    days
+}
@@ -0,0 +1,184 @@
+use crate::{DayResult, PartResult};
+use serde::{de, Deserialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+use std::{fs, io};
+
+/// Known answers for a year, loaded from `input/<year>/answers.toml`
+///
+/// The file holds one table per day, with `part_1`/`part_2` for the real input and
+/// `demo_1`/`demo_2` for the demo input, e.g.
+///
+/// ```toml
+/// [day1]
+/// demo_1 = 24000
+/// part_1 = 74394
+/// part_2 = "212836"
+/// ```
+///
+/// Answers are integers, or strings for anything else (e.g. the letters drawn by day 10).
+#[derive(Debug, Default, Clone)]
+pub struct Answers {
+    days: BTreeMap<u32, BTreeMap<String, String>>,
+}
+
+/// An answer as written in the file
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Answer {
+    Integer(i64),
+    String(String),
+}
+
+impl Answers {
+    /// Load the answers for `year`, from `input/<year>/answers.toml`
+    pub fn load_year(year: u32) -> io::Result<Self> {
+        Self::load(&crate::input::year_dir(year).join("answers.toml"))
+    }
+
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let s = fs::read_to_string(path)?;
+        Self::parse(&s).map_err(|e| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{}: {e}", path.display()),
+            )
+        })
+    }
+
+    pub fn parse(s: &str) -> Result<Self, toml::de::Error> {
+        let tables: BTreeMap<String, BTreeMap<String, Answer>> = toml::from_str(s)?;
+        let mut days = BTreeMap::new();
+        for (name, answers) in tables {
+            let day = name
+                .strip_prefix("day")
+                .and_then(|n| n.parse().ok())
+                .ok_or_else(|| {
+                    de::Error::custom(format!("expected a table like [day1], got [{name}]"))
+                })?;
+            let answers = answers
+                .into_iter()
+                .map(|(key, answer)| {
+                    let answer = match answer {
+                        Answer::Integer(n) => n.to_string(),
+                        Answer::String(s) => s,
+                    };
+                    (key, answer)
+                })
+                .collect();
+            days.insert(day, answers);
+        }
+        Ok(Self { days })
+    }
+
+    pub fn get(&self, day: u32, key: &str) -> Option<&str> {
+        self.days.get(&day)?.get(key).map(String::as_str)
+    }
+
+    /// Compare both parts of `result` to the stored answers for the real (or demo) input
+    pub fn check(&self, result: &DayResult, demo: bool) -> [Verdict; 2] {
+        let prefix = if demo { "demo" } else { "part" };
+        let check_part = |i: usize, part: &Option<PartResult>| {
+            let expected = self.get(result.day, &format!("{prefix}_{i}"));
+            let Some(part) = part else {
+                return Verdict::NotRun;
+            };
+            match (&part.answer, expected) {
+                (Err(e), _) => Verdict::Error(e.clone()),
+                (Ok(actual), None) => Verdict::Missing(actual.clone()),
+                (Ok(actual), Some(expected)) if actual == expected => Verdict::Pass,
+                (Ok(actual), Some(expected)) => Verdict::Fail {
+                    expected: expected.to_string(),
+                    actual: actual.clone(),
+                },
+            }
+        };
+        [check_part(1, &result.part_1), check_part(2, &result.part_2)]
+    }
+}
+
+/// The result of comparing a part's answer against the stored answer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Pass,
+    Fail {
+        expected: String,
+        actual: String,
+    },
+    /// No stored answer to compare against, holds the actual answer
+    Missing(String),
+    /// The part failed to produce an answer
+    Error(String),
+    NotRun,
+}
+
+impl Verdict {
+    pub fn is_failure(&self) -> bool {
+        matches!(self, Self::Fail { .. } | Self::Error(_))
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Pass => f.write_str("PASS"),
+            Self::Fail { expected, actual } => {
+                write!(f, "FAIL (expected {expected:?}, got {actual:?})")
+            }
+            Self::Missing(actual) => write!(f, "MISSING (got {actual:?})"),
+            Self::Error(e) => write!(f, "FAIL ({e})"),
+            Self::NotRun => f.write_str("NOT RUN"),
+        }
+    }
+}
+
+/// The stored answer used by `day_test!`, panics if there isn't one
+#[cfg(test)]
+pub(crate) fn expected_answer(year: u32, day: u32, key: &str) -> String {
+    let path = crate::input::crate_dir()
+        .join(crate::input::year_dir(year))
+        .join("answers.toml");
+    let answers = Answers::load(&path).unwrap();
+    match answers.get(day, key) {
+        Some(answer) => answer.to_string(),
+        None => panic!("No {key} answer for day {day} in input/{year}/answers.toml"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let answers = Answers::parse(
+            r#"
+# comment
+[day1]
+part_1 = 74_394 # trailing comment
+demo_1 = "CMZ"
+
+[ day10 ]
+part_2 = """
+
+#..#
+"quoted" \\"""
+"#,
+        )
+        .unwrap();
+        assert_eq!(answers.get(1, "part_1"), Some("74394"));
+        assert_eq!(answers.get(1, "demo_1"), Some("CMZ"));
+        assert_eq!(answers.get(1, "part_2"), None);
+        assert_eq!(answers.get(10, "part_2"), Some("\n#..#\n\"quoted\" \\"));
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert!(Answers::parse("part_1 = 1").is_err());
+        assert!(Answers::parse("[day1]\n\npart_1 = \"oops").is_err());
+        assert!(Answers::parse("[day1]\npart_1 = 1.5").is_err());
+        let err = Answers::parse("[dayone]\npart_1 = 1").unwrap_err();
+        assert!(err.to_string().contains("[dayone]"), "{err}");
+    }
+}
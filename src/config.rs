@@ -0,0 +1,60 @@
+use std::path::PathBuf;
+use std::{env, fs, io};
+
+/// Where inputs are fetched from, and answers submitted to, unless `AOC_BASE_URL` is set
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+
+/// How to talk to the puzzle server
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Config {
+    /// Without a trailing `/`, e.g. `https://adventofcode.com`
+    pub base_url: String,
+    /// The value of the `session` cookie of a logged in user
+    pub session: Option<String>,
+}
+
+impl Config {
+    /// Load from the environment: the base URL from `AOC_BASE_URL`, and the session token from
+    /// `AOC_SESSION`, or the file at `AOC_SESSION_FILE` (by default `~/.adventofcode.session`)
+    pub fn load() -> io::Result<Self> {
+        let base_url = env::var("AOC_BASE_URL").unwrap_or_else(|_| DEFAULT_BASE_URL.to_string());
+        let session = match env::var("AOC_SESSION") {
+            Ok(session) => Some(session),
+            Err(_) => match session_file() {
+                Some(path) => match fs::read_to_string(path) {
+                    Ok(session) => Some(session),
+                    Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+                    Err(e) => return Err(e),
+                },
+                None => None,
+            },
+        };
+        Ok(Self::new(base_url, session))
+    }
+
+    pub fn new(base_url: impl Into<String>, session: Option<String>) -> Self {
+        let base_url = base_url.into().trim_end_matches('/').to_string();
+        let session = session
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty());
+        Self { base_url, session }
+    }
+
+    /// The session token, or an error explaining how to set it
+    pub fn session(&self) -> io::Result<&str> {
+        self.session.as_deref().ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                "no session token, set AOC_SESSION or save it in ~/.adventofcode.session",
+            )
+        })
+    }
+}
+
+fn session_file() -> Option<PathBuf> {
+    if let Some(path) = env::var_os("AOC_SESSION_FILE") {
+        return Some(path.into());
+    }
+    let home = env::var_os("HOME")?;
+    Some(PathBuf::from(home).join(".adventofcode.session"))
+}
@@ -0,0 +1,188 @@
+//! Inputs encrypted at rest, as `dayN.txt.enc` next to where `dayN.txt` would be
+//!
+//! The key is 32 bytes as hex, from `AOC_INPUT_KEY`, or the file at `AOC_INPUT_KEY_FILE` (by
+//! default `.input_key` in the crate's directory, which is git ignored). Files are
+//! ChaCha20-Poly1305 encrypted, stored as [`MAGIC`], then the nonce, then the ciphertext.
+
+use chacha20poly1305::aead::{Aead, KeyInit, OsRng};
+use chacha20poly1305::{AeadCore, ChaCha20Poly1305, Nonce};
+use std::ffi::OsString;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+use std::{env, fs, io};
+
+/// The start of every encrypted file
+pub const MAGIC: &[u8] = b"AOCENC1\n";
+const NONCE_LEN: usize = 12;
+
+/// The key file used when `AOC_INPUT_KEY_FILE` isn't set, relative to the crate's directory
+pub const DEFAULT_KEY_FILE: &str = ".input_key";
+
+pub struct Key(chacha20poly1305::Key);
+
+impl Key {
+    /// Load the key from the environment, `None` if there isn't one
+    pub fn load() -> io::Result<Option<Self>> {
+        let hex = match env::var("AOC_INPUT_KEY") {
+            Ok(hex) => hex,
+            Err(_) => match fs::read_to_string(key_file()) {
+                Ok(hex) => hex,
+                Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+                Err(e) => return Err(e),
+            },
+        };
+        Self::from_hex(hex.trim()).map(Some).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                "invalid input key, expected 64 hex digits",
+            )
+        })
+    }
+
+    /// Load the key, or fail explaining how to provide one
+    pub fn require() -> io::Result<Self> {
+        // Not `NotFound`, which would mean the input itself is missing
+        Self::load()?.ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                format!(
+                    "no input key, set AOC_INPUT_KEY or save it in {}",
+                    key_file().display()
+                ),
+            )
+        })
+    }
+
+    /// A new random key
+    pub fn generate() -> Self {
+        Self(ChaCha20Poly1305::generate_key(&mut OsRng))
+    }
+
+    /// Save as the key file, only readable by the current user, failing if it already exists
+    pub fn save_new(&self) -> io::Result<PathBuf> {
+        let path = key_file();
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+        io::Write::write_all(&mut options.open(&path)?, (self.to_hex() + "\n").as_bytes())?;
+        Ok(path)
+    }
+
+    pub fn from_hex(hex: &str) -> Option<Self> {
+        if hex.len() != 64 || !hex.is_ascii() {
+            return None;
+        }
+        let mut key = chacha20poly1305::Key::default();
+        for (byte, digits) in key.iter_mut().zip(hex.as_bytes().chunks(2)) {
+            *byte = u8::from_str_radix(std::str::from_utf8(digits).ok()?, 16).ok()?;
+        }
+        Some(Self(key))
+    }
+
+    pub fn to_hex(&self) -> String {
+        self.0.iter().fold(String::with_capacity(64), |mut s, b| {
+            write!(s, "{b:02x}").unwrap();
+            s
+        })
+    }
+
+    pub fn encrypt(&self, plaintext: &[u8]) -> Vec<u8> {
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = ChaCha20Poly1305::new(&self.0)
+            .encrypt(&nonce, plaintext)
+            .expect("encryption can't fail");
+        [MAGIC, &nonce, &ciphertext].concat()
+    }
+
+    /// Fails if `data` isn't encrypted with this key, or has been modified
+    pub fn decrypt(&self, data: &[u8]) -> io::Result<Vec<u8>> {
+        let invalid = |message| io::Error::new(io::ErrorKind::InvalidData, message);
+        let data = data
+            .strip_prefix(MAGIC)
+            .ok_or_else(|| invalid("not an encrypted input"))?;
+        if data.len() < NONCE_LEN {
+            return Err(invalid("encrypted input is truncated"));
+        }
+        let (nonce, ciphertext) = data.split_at(NONCE_LEN);
+        ChaCha20Poly1305::new(&self.0)
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| invalid("unable to decrypt input, wrong key?"))
+    }
+}
+
+fn key_file() -> PathBuf {
+    env::var_os("AOC_INPUT_KEY_FILE").map_or_else(
+        || crate::input::crate_dir().join(DEFAULT_KEY_FILE),
+        PathBuf::from,
+    )
+}
+
+/// Where the encrypted version of the file at `path` is kept, `path` with `.enc` appended
+pub fn encrypted_path(path: &Path) -> PathBuf {
+    let mut path = OsString::from(path);
+    path.push(".enc");
+    path.into()
+}
+
+/// Decrypt the encrypted version of the input at `path`
+pub fn read_encrypted(path: &Path) -> io::Result<String> {
+    let encrypted_path = encrypted_path(path);
+    let data = fs::read(&encrypted_path)?;
+    let error =
+        |e: io::Error| io::Error::new(e.kind(), format!("{}: {e}", encrypted_path.display()));
+    let plaintext = Key::require()
+        .map_err(error)?
+        .decrypt(&data)
+        .map_err(error)?;
+    String::from_utf8(plaintext).map_err(|e| error(io::Error::new(io::ErrorKind::InvalidData, e)))
+}
+
+/// Encrypt the input at `path`, removing the plaintext, returning the path of the encrypted file
+pub fn encrypt_file(key: &Key, path: &Path) -> io::Result<PathBuf> {
+    let encrypted_path = encrypted_path(path);
+    fs::write(&encrypted_path, key.encrypt(&fs::read(path)?))?;
+    fs::remove_file(path)?;
+    Ok(encrypted_path)
+}
+
+/// Decrypt the encrypted version of the input at `path` to `path`, keeping the encrypted file
+pub fn decrypt_file(key: &Key, path: &Path) -> io::Result<()> {
+    let plaintext = key.decrypt(&fs::read(encrypted_path(path))?)?;
+    fs::write(path, plaintext)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encrypt_decrypt() {
+        let key = Key::generate();
+        assert_eq!(Key::from_hex(&key.to_hex()).unwrap().0, key.0);
+        assert!(Key::from_hex("00").is_none());
+
+        let encrypted = key.encrypt(b"1\n2\n");
+        assert!(encrypted.starts_with(MAGIC));
+        assert_eq!(key.decrypt(&encrypted).unwrap(), b"1\n2\n");
+        // A fresh nonce each time
+        assert_ne!(key.encrypt(b"1\n2\n"), encrypted);
+
+        assert!(Key::generate().decrypt(&encrypted).is_err());
+        let mut tampered = encrypted.clone();
+        *tampered.last_mut().unwrap() ^= 1;
+        assert!(key.decrypt(&tampered).is_err());
+        assert!(key.decrypt(b"1\n2\n").is_err());
+        assert!(key.decrypt(MAGIC).is_err());
+
+        let dir = std::env::temp_dir().join(format!("aoc_2022_crypt_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("day1.txt");
+        fs::write(&path, "input\n").unwrap();
+        assert_eq!(encrypt_file(&key, &path).unwrap(), dir.join("day1.txt.enc"));
+        assert!(!path.exists());
+        decrypt_file(&key, &path).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "input\n");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,3 +1,6 @@
+use crate::parse::{ParseError, Parser};
+use crate::Solution;
+
 const DISPLAY_WIDTH: usize = 240;
 
 #[derive(Debug, Copy, Clone)]
@@ -36,69 +39,66 @@ impl<'a> Cpu<'a> {
     }
 }
 
-pub fn generator(s: &str) -> Vec<Instruction> {
-    let mut result = Vec::with_capacity(1024);
+pub struct Day;
 
-    for line in s.lines() {
-        result.push(if line == "noop" {
-            Instruction::Noop
-        } else {
-            Instruction::Addx(line.strip_prefix("addx ").unwrap().parse().unwrap())
-        });
-    }
+impl Solution for Day {
+    type Input<'a> = Vec<Instruction>;
+    type Answer1 = i32;
+    type Answer2 = String;
 
-    result
-}
+    fn parse(s: &str) -> Result<Self::Input<'_>, ParseError> {
+        let p = Parser::new(s);
+        let mut result = Vec::with_capacity(1024);
 
-pub fn part_1(instructions: &[Instruction]) -> i32 {
-    let mut cpu = Cpu::new(instructions);
+        for line in s.lines() {
+            result.push(if line == "noop" {
+                Instruction::Noop
+            } else {
+                Instruction::Addx(p.number(p.strip_prefix(line, "addx ")?)?)
+            });
+        }
 
-    for _ in 0..19 {
-        cpu.advance();
+        Ok(result)
     }
-    let mut x_values_sum = 20 * cpu.x;
 
-    for i in 0..5 {
-        for _ in 0..40 {
+    fn part_1(instructions: &Self::Input<'_>) -> i32 {
+        let mut cpu = Cpu::new(instructions);
+
+        for _ in 0..19 {
             cpu.advance();
         }
-        x_values_sum += cpu.x * ((i + 1) * 40 + 20);
-    }
+        let mut x_values_sum = 20 * cpu.x;
 
-    x_values_sum
-}
+        for i in 0..5 {
+            for _ in 0..40 {
+                cpu.advance();
+            }
+            x_values_sum += cpu.x * ((i + 1) * 40 + 20);
+        }
 
-pub fn part_2(instructions: &[Instruction]) -> String {
-    // 6 rows, with a newline
-    let mut result = String::with_capacity((DISPLAY_WIDTH + 1) * 6);
+        x_values_sum
+    }
 
-    let mut cpu = Cpu::new(instructions);
+    fn part_2(instructions: &Self::Input<'_>) -> String {
+        // 6 rows, with a newline
+        let mut result = String::with_capacity((DISPLAY_WIDTH + 1) * 6);
 
-    for _ in 0..6 {
-        result.push('\n');
-        for x in 0..40 {
-            let ch = if cpu.x.abs_diff(x) < 2 { '#' } else { '.' };
-            result.push(ch);
-            cpu.advance();
+        let mut cpu = Cpu::new(instructions);
+
+        for _ in 0..6 {
+            result.push('\n');
+            for x in 0..40 {
+                let ch = if cpu.x.abs_diff(x) < 2 { '#' } else { '.' };
+                result.push(ch);
+                cpu.advance();
+            }
         }
-    }
 
-    result
+        result
+    }
 }
 
-super::day_test! {demo_1 == 13140}
-super::day_test! {demo_2 == "
-##..##..##..##..##..##..##..##..##..##..
-###...###...###...###...###...###...###.
-####....####....####....####....####....
-#####.....#####.....#####.....#####.....
-######......######......######......####
-#######.......#######.......#######....."}
-super::day_test! {part_1 == 13720}
-super::day_test! {part_2 == "
-####.###..#..#.###..#..#.####..##..#..#.
-#....#..#.#..#.#..#.#..#....#.#..#.#..#.
-###..###..#..#.#..#.####...#..#....####.
-#....#..#.#..#.###..#..#..#...#....#..#.
-#....#..#.#..#.#.#..#..#.#....#..#.#..#.
-#....###...##..#..#.#..#.####..##..#..#."}
+super::day_test! {demo_1}
+super::day_test! {demo_2}
+super::day_test! {part_1}
+super::day_test! {part_2}
@@ -1,3 +1,5 @@
+use crate::parse::{ParseError, Parser};
+use crate::Solution;
 use std::{cmp, mem};
 
 type Worry = u64;
@@ -55,6 +57,7 @@ impl Monkeys {
 
             // Ensure all indexes are in range, and non-overlapping with each other or `i`
             // this assurance is used in the unsafe block when getting all 3 monkeys via &mut at the same time
+            // (bad inputs are already rejected when parsing, this only guards the unsafe block)
             assert!(true_monkey < monkeys_len);
             assert!(false_monkey < monkeys_len);
 
@@ -121,70 +124,105 @@ impl Monkeys {
     }
 }
 
-pub fn generator(s: &str) -> Monkeys {
-    let mut result = Vec::with_capacity(16);
-
-    let mut lines = s.lines();
-
-    while let Some(_header) = lines.next() {
-        let worries: Vec<Worry> = lines
-            .next()
-            .unwrap()
-            .strip_prefix("  Starting items: ")
-            .unwrap()
-            .split(", ")
-            .map(|s| s.parse().unwrap())
-            .collect();
-        let op_line = lines
-            .next()
-            .unwrap()
-            .strip_prefix("  Operation: new = old ")
-            .unwrap();
-        let op_value = &op_line[2..];
-        let op = match op_line.as_bytes()[0] {
-            b'*' if op_value == "old" => Op::Square,
-            b'*' => Op::Mul(op_value.parse().unwrap()),
-            b'+' => Op::Add(op_value.parse().unwrap()),
-            _ => panic!("Unimplemented operation"),
-        };
+pub struct Day;
+
+impl Solution for Day {
+    type Input<'a> = Monkeys;
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(s: &str) -> Result<Self::Input<'_>, ParseError> {
+        let p = Parser::new(s);
+        let mut result = Vec::with_capacity(16);
+
+        // Where each monkey's targets are, to report invalid ones
+        let mut targets = Vec::with_capacity(16);
+        let mut lines = s.lines();
+        while let Some(header) = lines.next() {
+            p.strip_prefix(header, "Monkey ")?;
+            // The rest of the next line, which should start with `prefix`
+            let mut next_line = |prefix: &str| {
+                let line = p.next(&mut lines, s, &format!("{prefix:?}"))?;
+                p.strip_prefix(line, prefix)
+            };
+
+            let worries = next_line("  Starting items: ")?
+                .split(", ")
+                .map(|s| p.number(s))
+                .collect::<Result<Vec<Worry>, _>>()?;
+            let op_line = next_line("  Operation: new = old ")?;
+            let (operator, operand) = p.split_once(op_line, " ")?;
+            let op = match (operator, operand) {
+                ("*", "old") => Op::Square,
+                ("*", n) => Op::Mul(p.number(n)?),
+                ("+", n) => Op::Add(p.number(n)?),
+                _ => return Err(p.error(op_line, "an operation, \"*\" or \"+\"")),
+            };
+
+            let div_at = next_line("  Test: divisible by ")?;
+            let div: Worry = p.number(div_at)?;
+            if div == 0 {
+                return Err(p.error(div_at, "a divisor above 0"));
+            }
+
+            let true_at = next_line("    If true: throw to monkey ")?;
+            let false_at = next_line("    If false: throw to monkey ")?;
+            let true_idx: MonkeyId = p.number(true_at)?;
+            let false_idx: MonkeyId = p.number(false_at)?;
+            targets.push((true_at, false_at));
+
+            // empty line (if any)
+            let _ = lines.next();
+
+            result.push(Monkey {
+                worries,
+                op,
+                divisible_check: div,
+                true_monkey: true_idx,
+                false_monkey: false_idx,
+                items_inspected: 0,
+            });
+        }
 
-        let div: Worry = lines
-            .next()
-            .unwrap()
-            .strip_prefix("  Test: divisible by ")
-            .unwrap()
-            .parse()
-            .unwrap();
-
-        let true_idx: MonkeyId = lines
-            .next()
-            .unwrap()
-            .strip_prefix("    If true: throw to monkey ")
-            .unwrap()
-            .parse()
-            .unwrap();
-        let false_idx: MonkeyId = lines
-            .next()
-            .unwrap()
-            .strip_prefix("    If false: throw to monkey ")
-            .unwrap()
-            .parse()
-            .unwrap();
-
-        // empty line (if any)
-        let _ = lines.next();
-
-        result.push(Monkey {
-            worries,
-            op,
-            divisible_check: div,
-            true_monkey: true_idx,
-            false_monkey: false_idx,
-            items_inspected: 0,
-        });
+        if result.is_empty() {
+            return Err(p.error(s, "\"Monkey \""));
+        }
+        for (i, (monkey, &(true_at, false_at))) in result.iter().zip(&targets).enumerate() {
+            for (target, at) in [(monkey.true_monkey, true_at), (monkey.false_monkey, false_at)] {
+                if usize::from(target) >= result.len() {
+                    return Err(p.error(at, format!("a monkey below {}", result.len())));
+                }
+                if usize::from(target) == i {
+                    return Err(p.error(at, "a monkey other than itself"));
+                }
+            }
+            if monkey.true_monkey == monkey.false_monkey {
+                return Err(p.error(false_at, "a different monkey than if true"));
+            }
+        }
+
+        Ok(Monkeys::new(result.into_boxed_slice()))
     }
 
-    Monkeys::new(result.into_boxed_slice())
+    fn part_1(monkeys: &Self::Input<'_>) -> u64 {
+        let mut monkeys = monkeys.clone();
+
+        for _ in 0..20 {
+            monkeys.step::<true>();
+        }
+
+        monkeys.monkey_business()
+    }
+
+    fn part_2(monkeys: &Self::Input<'_>) -> u64 {
+        let mut monkeys = monkeys.clone();
+
+        for _ in 0..10_000 {
+            monkeys.step::<false>();
+        }
+
+        monkeys.monkey_business()
+    }
 }
 
 fn gcd(mut a: Worry, mut b: Worry) -> Worry {
@@ -198,26 +236,6 @@ fn lcm(a: Worry, b: Worry) -> Worry {
     a / gcd(a, b) * b
 }
 
-pub fn part_1(monkeys: &Monkeys) -> u64 {
-    let mut monkeys = monkeys.clone();
-
-    for _ in 0..20 {
-        monkeys.step::<true>();
-    }
-
-    monkeys.monkey_business()
-}
-
-pub fn part_2(monkeys: &Monkeys) -> u64 {
-    let mut monkeys = monkeys.clone();
-
-    for _ in 0..10_000 {
-        monkeys.step::<false>();
-    }
-
-    monkeys.monkey_business()
-}
-
 pub fn partition<T, P>(data: &mut [T], predicate: P) -> (&mut [T], &mut [T])
 where
     P: FnMut(&T) -> bool,
@@ -265,7 +283,31 @@ where
     true_count
 }
 
-super::day_test! {demo_1 == 10605}
-super::day_test! {part_1 == 61005}
-super::day_test! {demo_2 == 2713310158}
-super::day_test! {part_2 == 20567144694}
+super::day_test! {demo_1}
+super::day_test! {part_1}
+super::day_test! {demo_2}
+super::day_test! {part_2}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn invalid_targets() {
+        let demo = crate::day_test!(@demo_input);
+        let error = |from, to| Day::parse(&demo.replacen(from, to, 1)).unwrap_err().to_string();
+        assert_eq!(
+            error("If true: throw to monkey 2", "If true: throw to monkey 4"),
+            "5:30: expected a monkey below 4"
+        );
+        assert_eq!(
+            error("If false: throw to monkey 3", "If false: throw to monkey 0"),
+            "6:31: expected a monkey other than itself"
+        );
+        assert_eq!(
+            error("If false: throw to monkey 3", "If false: throw to monkey 2"),
+            "6:31: expected a different monkey than if true"
+        );
+        assert_eq!(Day::parse("").unwrap_err().to_string(), "1:1: expected \"Monkey \"");
+    }
+}
@@ -1,10 +1,11 @@
-use ahash::{HashMap, HashMapExt};
-use std::collections::BTreeMap;
-use std::iter;
+use crate::parse::{ParseError, Parser};
+use crate::search::{self, Path, Search};
+use crate::Solution;
 
-use crate::grid::NlGrid;
+use crate::geom::{Dir4, Point2};
+use crate::grid::{Grid, NlGrid};
 
-type Pos = (i32, i32);
+type Pos = Point2<i32>;
 
 const fn height(h: u8) -> u8 {
     match h {
@@ -25,98 +26,153 @@ pub struct Map<'a> {
     end: Pos,
 }
 
-pub fn generator(s: &str) -> Map {
-    let grid = NlGrid::new(s);
+pub struct Day;
 
-    let start = grid.position_of(b'S').unwrap();
-    let end = grid.position_of(b'E').unwrap();
+impl Solution for Day {
+    type Input<'a> = Map<'a>;
+    type Answer1 = usize;
+    type Answer2 = usize;
 
-    Map { grid, start, end }
-}
+    fn parse(s: &str) -> Result<Self::Input<'_>, ParseError> {
+        let p = Parser::new(s);
+        let grid = NlGrid::new(s);
 
-pub fn part_1(map: &Map) -> u32 {
-    let mut came_from = HashMap::with_capacity(1024);
-    let mut cost_so_far = HashMap::with_capacity(1024);
-    a_star(
-        map.grid,
-        iter::once(map.start),
-        map.end,
-        &mut came_from,
-        &mut cost_so_far,
-    );
-    cost_so_far[&map.end]
-}
+        let start = grid
+            .position_of(b'S')
+            .ok_or_else(|| p.error_after(s, "a start position, \"S\""))?
+            .into();
+        let end = grid
+            .position_of(b'E')
+            .ok_or_else(|| p.error_after(s, "an end position, \"E\""))?
+            .into();
 
-pub fn part_2(map: &Map) -> u32 {
-    let mut came_from = HashMap::with_capacity(1024);
-    let mut cost_so_far = HashMap::with_capacity(1024);
-    a_star(
-        map.grid,
-        map.grid.multi_position(b'a'),
-        map.end,
-        &mut came_from,
-        &mut cost_so_far,
-    );
-    cost_so_far[&map.end]
-}
+        Ok(Map { grid, start, end })
+    }
+
+    // Panics if E can't be reached, so there's no answer to check or submit
+    fn part_1(map: &Self::Input<'_>) -> usize {
+        map.route(false).expect("E is unreachable from S").cost
+    }
 
-const fn heuristic(_src: Pos, _dst: Pos) -> u32 {
-    // Unfortunately, the heuristic doesn't help much
-    // src.0.abs_diff(dst.0) + src.1.abs_diff(dst.1)
-    0
+    fn part_2(map: &Self::Input<'_>) -> usize {
+        map.route(true).expect("E is unreachable from any a").cost
+    }
 }
 
-fn a_star(
-    grid: NlGrid,
-    starts: impl Iterator<Item = Pos>,
-    end: Pos,
-    came_from: &mut HashMap<Pos, Pos>,
-    cost_so_far: &mut HashMap<Pos, u32>,
-) {
-    const DIFFS: [(i32, i32); 4] = [(1, 0), (-1, 0), (0, 1), (0, -1)];
-    let mut frontier = BTreeMap::<u32, Vec<(Pos, u8)>>::new();
-
-    let start_items = frontier.entry(0).or_default();
-    for start in starts {
-        let start_height = grid.get(start.0, start.1).unwrap();
-        start_items.push((start, start_height));
-        came_from.insert(start, start);
-        cost_so_far.insert(start, 0);
+impl Map<'_> {
+    /// The shortest route to E, from S, or from whichever `a` is closest if `from_any_a`
+    pub fn route(&self, from_any_a: bool) -> Option<Path<Pos, usize>> {
+        let mut route = if from_any_a {
+            self.search_from_end(|pos| self.height(pos) == b'a')
+        } else {
+            self.search_from_end(|pos| pos == self.start)
+        }
+        .path()?;
+        // Found backwards, from E
+        route.nodes.reverse();
+        Some(route)
+    }
+
+    /// The number of steps from each position to E, `None` where E can't be reached from
+    pub fn distance_field(&self) -> Grid<Option<usize>> {
+        let mut field = Grid::filled(self.grid.width(), self.grid.height(), None);
+        for (pos, steps) in self.search_from_end(|_| false).costs() {
+            field.set(pos.x as usize, pos.y as usize, Some(steps));
+        }
+        field
     }
 
-    'outer: while let Some((_, items)) = frontier.pop_first() {
-        for (current, current_height) in items {
-            if current == end {
-                break 'outer;
-            }
-
-            let current_cost = cost_so_far[&current];
-            for diff in DIFFS {
-                let new_pos = (current.0 + diff.0, current.1 + diff.1);
-                let new_cost = current_cost + 1;
-
-                let Some(new_height) = grid.get(new_pos.0, new_pos.1) else { continue };
-                if !can_climb(current_height, new_height) {
-                    continue;
-                }
-                if cost_so_far
-                    .get(&new_pos)
-                    .map_or(true, |&cost| new_cost < cost)
-                {
-                    cost_so_far.insert(new_pos, new_cost);
-                    let priority = new_cost + heuristic(new_pos, end);
-                    frontier
-                        .entry(priority)
-                        .or_default()
-                        .push((new_pos, new_height));
-                    came_from.insert(new_pos, current);
-                }
-            }
+    /// Search from E, climbing backwards, until `is_goal`
+    fn search_from_end(&self, is_goal: impl FnMut(Pos) -> bool) -> Search<Pos, usize> {
+        let successors = |current: Pos| {
+            let current_height = self.grid.get(current.x, current.y).unwrap();
+            Dir4::ALL.into_iter().filter_map(move |dir| {
+                let new_pos = current + dir.offset();
+                let new_height = self.grid.get(new_pos.x, new_pos.y)?;
+                can_climb(new_height, current_height).then_some(new_pos)
+            })
+        };
+        search::bfs([self.end], successors, is_goal)
+    }
+
+    fn height(&self, pos: Pos) -> u8 {
+        height(self.grid.get(pos.x, pos.y).unwrap())
+    }
+
+    /// Draw `route` like the puzzle does, each step as an arrow towards the next, with the rest
+    /// of the map as `.`, except for E
+    pub fn render_route(&self, route: &Path<Pos, usize>) -> String {
+        let mut drawing = Grid::filled(self.grid.width(), self.grid.height(), b'.');
+        let mut draw = |pos: Pos, ch| drawing.set(pos.x as usize, pos.y as usize, ch);
+        for step in route.nodes.windows(2) {
+            let dir = Dir4::ALL
+                .into_iter()
+                .find(|&dir| step[0] + dir.offset() == step[1])
+                .expect("each step of a route is to a neighbour");
+            draw(step[0], arrow(dir));
         }
+        draw(self.end, b'E');
+
+        let mut out = String::with_capacity((drawing.width() + 1) * drawing.height());
+        for row in drawing.rows() {
+            out.extend(row.iter().map(|&b| char::from(b)));
+            out.push('\n');
+        }
+        out
+    }
+}
+
+const fn arrow(dir: Dir4) -> u8 {
+    match dir {
+        Dir4::Up => b'^',
+        Dir4::Right => b'>',
+        Dir4::Down => b'v',
+        Dir4::Left => b'<',
     }
 }
 
-super::day_test! {demo_1 == 31}
-super::day_test! {part_1 == 440}
-super::day_test! {demo_2 == 29}
-super::day_test! {part_2 == 439}
+super::day_test! {demo_1}
+super::day_test! {part_1}
+super::day_test! {demo_2}
+super::day_test! {part_2}
+
+#[test]
+fn demo_route() {
+    let input = super::day_test!(@demo_input);
+    let map = Day::parse(&input).unwrap();
+
+    let route = map.route(false).unwrap();
+    assert_eq!((route.start(), route.end()), (map.start, map.end));
+    // The puzzle shows a different route of the same length
+    assert_eq!(
+        map.render_route(&route),
+        ">>vv<<<<\n..vvv<<^\n..vv>E^^\n..v>>>^^\n..>>>>>^\n"
+    );
+
+    let route = map.route(true).unwrap();
+    assert_eq!(route.cost, 29);
+    assert_eq!(map.height(route.start()), b'a');
+    let drawing = map.render_route(&route);
+    assert_eq!(drawing.matches(['>', 'v', '<', '^']).count(), 29);
+}
+
+#[test]
+fn distances() {
+    let input = super::day_test!(@demo_input);
+    let map = Day::parse(&input).unwrap();
+    let field = map.distance_field();
+    assert_eq!(field[(5, 2)], Some(0));
+    assert_eq!(field[(0, 0)], Some(31));
+    assert_eq!(field.cells().iter().flatten().count(), 40);
+
+    // E is too high to climb to
+    let map = Day::parse("SbcdE\n").unwrap();
+    assert_eq!(map.route(false), None);
+    assert_eq!(map.distance_field().row(0), [None, None, None, None, Some(0)]);
+
+    // Which fails both parts, rather than giving an answer
+    let result = crate::run_solution::<Day>(12, "SbcdE\n", crate::Parts::Both);
+    let error = |part: Option<crate::PartResult>| part.unwrap().answer.unwrap_err();
+    assert_eq!(error(result.part_1), "panic'd: E is unreachable from S");
+    assert_eq!(error(result.part_2), "panic'd: E is unreachable from any a");
+}
@@ -1,3 +1,5 @@
+use crate::parse::{ParseError, Parser};
+use crate::Solution;
 use std::cmp::Ordering;
 
 #[derive(Clone, Debug)]
@@ -50,83 +52,103 @@ impl Ord for Item {
     }
 }
 
-pub fn generator(s: &str) -> Vec<Vec<Item>> {
-    let mut result = Vec::with_capacity(512);
-    let mut stack: Vec<Vec<Item>> = Vec::with_capacity(32);
-    for line in s.lines() {
-        if line.is_empty() {
-            continue;
-        }
-        let mut n = None;
-        // Ignore the last pop, so we end up with a single list at the top level
-        for b in line[..line.len() - 1].bytes() {
-            match b {
-                b'[' => stack.push(Vec::with_capacity(32)),
-                b']' => {
-                    if let Some(n) = n.take() {
-                        stack.last_mut().unwrap().push(Item::Num(n));
+pub struct Day;
+
+impl Solution for Day {
+    type Input<'a> = Vec<Vec<Item>>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(s: &str) -> Result<Self::Input<'_>, ParseError> {
+        let p = Parser::new(s);
+        let mut result = Vec::with_capacity(512);
+        let mut stack: Vec<Vec<Item>> = Vec::with_capacity(32);
+        for line in s.lines() {
+            if line.is_empty() {
+                continue;
+            }
+            if !line.starts_with('[') {
+                return Err(p.error(line, "\"[\""));
+            }
+            if !line.ends_with(']') {
+                return Err(p.error_after(line, "\"]\""));
+            }
+            let mut n = None;
+            // Ignore the last pop, so we end up with a single list at the top level
+            for (i, b) in line[..line.len() - 1].bytes().enumerate() {
+                let unexpected = || p.error(&line[i..], "a number, or a list");
+                match b {
+                    b'[' => stack.push(Vec::with_capacity(32)),
+                    b']' => {
+                        if let Some(n) = n.take() {
+                            stack.last_mut().unwrap().push(Item::Num(n));
+                        }
+                        let finished_list = stack.pop().unwrap();
+                        // Closing the outer list early
+                        let parent = stack.last_mut().ok_or_else(unexpected)?;
+                        parent.push(Item::List(finished_list));
                     }
-                    let finished_list = stack.pop().unwrap();
-                    stack.last_mut().unwrap().push(Item::List(finished_list));
-                }
-                b',' => {
-                    if let Some(n) = n.take() {
-                        stack.last_mut().unwrap().push(Item::Num(n));
+                    b',' => {
+                        if let Some(n) = n.take() {
+                            stack.last_mut().unwrap().push(Item::Num(n));
+                        }
                     }
-                }
-                digit => {
-                    debug_assert!((b'0'..=b'9').contains(&digit));
-                    let current_n = n.get_or_insert(0);
-                    *current_n = *current_n * 10 + (digit - b'0');
+                    b'0'..=b'9' => {
+                        let current_n = n.get_or_insert(0);
+                        *current_n = *current_n * 10 + (b - b'0');
+                    }
+                    _ => return Err(unexpected()),
                 }
             }
+            if stack.len() != 1 {
+                return Err(p.error(&line[line.len() - 1..], "\"]\""));
+            }
+            let mut message = stack.pop().unwrap();
+            if let Some(n) = n {
+                message.push(Item::Num(n))
+            }
+            result.push(message);
         }
-        let mut message = stack.pop().unwrap();
-        if let Some(n) = n {
-            message.push(Item::Num(n))
-        }
-        result.push(message);
-        assert!(stack.is_empty());
+        Ok(result)
     }
-    result
-}
 
-pub fn part_1(messages: &[Vec<Item>]) -> usize {
-    let mut sum = 0;
-    for (i, two_messages) in messages.chunks(2).enumerate() {
-        let [m1, m2] = two_messages else { panic!("Only 2 items") };
-        if m1 < m2 {
-            sum += i + 1;
+    fn part_1(messages: &Self::Input<'_>) -> usize {
+        let mut sum = 0;
+        for (i, two_messages) in messages.chunks(2).enumerate() {
+            let [m1, m2] = two_messages else { panic!("Only 2 items") };
+            if m1 < m2 {
+                sum += i + 1;
+            }
         }
+        sum
     }
-    sum
-}
 
-pub fn part_2(messages: &[Vec<Item>]) -> usize {
-    let divider_1 = vec![Item::List(vec![Item::Num(2)])];
-    let divider_2 = vec![Item::List(vec![Item::Num(6)])];
-
-    let mut num_lt_divider_1 = 0;
-    let mut num_lt_divider_2 = 0;
-    for message in messages {
-        if message < &divider_1 {
-            num_lt_divider_1 += 1;
-        } else if message < &divider_2 {
-            num_lt_divider_2 += 1;
+    fn part_2(messages: &Self::Input<'_>) -> usize {
+        let divider_1 = vec![Item::List(vec![Item::Num(2)])];
+        let divider_2 = vec![Item::List(vec![Item::Num(6)])];
+
+        let mut num_lt_divider_1 = 0;
+        let mut num_lt_divider_2 = 0;
+        for message in messages {
+            if message < &divider_1 {
+                num_lt_divider_1 += 1;
+            } else if message < &divider_2 {
+                num_lt_divider_2 += 1;
+            }
         }
-    }
 
-    num_lt_divider_2 += num_lt_divider_1;
+        num_lt_divider_2 += num_lt_divider_1;
 
-    // 1 indexed
-    let idx1 = num_lt_divider_1 + 1;
-    // 1 indexed, plus the position the first divider would have taken
-    let idx2 = num_lt_divider_2 + 2;
+        // 1 indexed
+        let idx1 = num_lt_divider_1 + 1;
+        // 1 indexed, plus the position the first divider would have taken
+        let idx2 = num_lt_divider_2 + 2;
 
-    idx1 * idx2
+        idx1 * idx2
+    }
 }
 
-super::day_test! {demo_1 == 13}
-super::day_test! {part_1 == 5198}
-super::day_test! {demo_2 == 140}
-super::day_test! {part_2 == 22344}
+super::day_test! {demo_1}
+super::day_test! {part_1}
+super::day_test! {demo_2}
+super::day_test! {part_2}
@@ -1,9 +1,12 @@
+use crate::geom::{Coord, Point2};
+use crate::parse::{ParseError, Parser};
+use crate::Solution;
 use ahash::{HashMap, HashMapExt};
 use std::cmp;
 use std::collections::BTreeSet;
 use std::ops::Range;
 
-type Point = (i32, i32);
+type Point = Point2<i32>;
 
 #[derive(Debug, Copy, Clone)]
 pub struct SensorBeacon {
@@ -14,38 +17,55 @@ pub struct SensorBeacon {
 
 impl SensorBeacon {
     fn x_range_at_y(self, y: i32) -> Range<i32> {
-        let y_dist = self.sensor.1.abs_diff(y);
+        let y_dist = self.sensor.y.abs_diff(y);
 
         let width = (self.dist * 2 + 1).saturating_sub(2 * y_dist);
-        let center = self.sensor.0;
+        let center = self.sensor.x;
 
         let start = center.sub_unsigned(width / 2);
         start..start.add_unsigned(width)
     }
 }
 
-fn parse_pos(pos: &str) -> Point {
-    let (x, y) = pos.split_once(", ").unwrap();
-    let x = x.strip_prefix("x=").unwrap();
-    let y = y.strip_prefix("y=").unwrap();
+fn parse_pos(p: &Parser, pos: &str) -> Result<Point, ParseError> {
+    let (x, y) = p.split_once(pos, ", ")?;
+    let x = p.strip_prefix(x, "x=")?;
+    let y = p.strip_prefix(y, "y=")?;
 
-    (x.parse().unwrap(), y.parse().unwrap())
+    Ok(Point::new(p.number(x)?, p.number(y)?))
 }
 
-pub fn generator(s: &str) -> Vec<SensorBeacon> {
-    let mut result = Vec::with_capacity(1024);
-    for line in s.lines() {
-        let line = line.strip_prefix("Sensor at ").unwrap();
-        let (sensor, beacon) = line.split_once(": closest beacon is at ").unwrap();
-        let sensor = parse_pos(sensor);
-        let beacon = parse_pos(beacon);
-        result.push(SensorBeacon {
-            sensor,
-            beacon,
-            dist: dist(sensor, beacon),
-        });
+pub struct Day;
+
+impl Solution for Day {
+    type Input<'a> = Vec<SensorBeacon>;
+    type Answer1 = u32;
+    type Answer2 = i64;
+
+    fn parse(s: &str) -> Result<Self::Input<'_>, ParseError> {
+        let p = Parser::new(s);
+        let mut result = Vec::with_capacity(1024);
+        for line in s.lines() {
+            let line = p.strip_prefix(line, "Sensor at ")?;
+            let (sensor, beacon) = p.split_once(line, ": closest beacon is at ")?;
+            let sensor = parse_pos(&p, sensor)?;
+            let beacon = parse_pos(&p, beacon)?;
+            result.push(SensorBeacon {
+                sensor,
+                beacon,
+                dist: sensor.manhattan(beacon),
+            });
+        }
+        Ok(result)
+    }
+
+    fn part_1(items: &Self::Input<'_>) -> u32 {
+        count_non_beacons_at_y(items, 2000000)
+    }
+
+    fn part_2(items: &Self::Input<'_>) -> i64 {
+        empty_spot_frequency::<4_000_000>(items)
     }
-    result
 }
 
 fn count_non_beacons_at_y(items: &[SensorBeacon], y: i32) -> u32 {
@@ -53,8 +73,8 @@ fn count_non_beacons_at_y(items: &[SensorBeacon], y: i32) -> u32 {
     let mut ranges: Vec<_> = items
         .iter()
         .filter_map(|sb| {
-            if sb.beacon.1 == y {
-                known_beacons.insert(sb.beacon.0);
+            if sb.beacon.y == y {
+                known_beacons.insert(sb.beacon.x);
             }
             let range = sb.x_range_at_y(y);
             (!range.is_empty()).then_some(range)
@@ -80,18 +100,14 @@ fn count_non_beacons_at_y(items: &[SensorBeacon], y: i32) -> u32 {
     res - known_beacons.len() as u32
 }
 
-pub fn part_1(items: &[SensorBeacon]) -> u32 {
-    count_non_beacons_at_y(items, 2000000)
-}
-
 fn find_empty_spot<const MAX_XY: i32>(items: &[SensorBeacon]) -> Point {
     let range = 0..=MAX_XY;
     let mut down_slope_intercept_counts = HashMap::<i32, u32>::with_capacity(items.len());
     let mut up_slope_intercept_counts = HashMap::<i32, u32>::with_capacity(items.len());
 
     items.iter().for_each(|item| {
-        let up_slope_intercept_center = item.sensor.1 - item.sensor.0;
-        let down_slope_intercept_center = item.sensor.1 + item.sensor.0;
+        let up_slope_intercept_center = item.sensor.y - item.sensor.x;
+        let down_slope_intercept_center = item.sensor.y + item.sensor.x;
 
         let extra_dist = item.dist + 1;
 
@@ -120,16 +136,16 @@ fn find_empty_spot<const MAX_XY: i32>(items: &[SensorBeacon]) -> Point {
 
     for &up_intercept in &up_slope_intercepts {
         for &down_intercept in &down_slope_intercepts {
-            let intersection = (
+            let intersection = Point::new(
                 (down_intercept - up_intercept) / 2,
                 (down_intercept + up_intercept) / 2,
             );
-            if !range.contains(&intersection.0) || !range.contains(&intersection.1) {
+            if !range.contains(&intersection.x) || !range.contains(&intersection.y) {
                 continue;
             }
             if items
                 .iter()
-                .all(|sb| dist(sb.sensor, intersection) > sb.dist)
+                .all(|sb| sb.sensor.manhattan(intersection) > sb.dist)
             {
                 return intersection;
             }
@@ -140,58 +156,23 @@ fn find_empty_spot<const MAX_XY: i32>(items: &[SensorBeacon]) -> Point {
 
 fn empty_spot_frequency<const MAX_XY: i32>(items: &[SensorBeacon]) -> i64 {
     let point = find_empty_spot::<MAX_XY>(items);
-    i64::from(point.0) * 4_000_000 + i64::from(point.1)
-}
-
-pub fn part_2(items: &[SensorBeacon]) -> i64 {
-    empty_spot_frequency::<4_000_000>(items)
-}
-
-fn dist(lhs: Point, rhs: Point) -> u32 {
-    lhs.0.abs_diff(rhs.0) + lhs.1.abs_diff(rhs.1)
-}
-
-trait SignedUnsigned {
-    type Unsigned;
-    fn sub_unsigned(self, other: Self::Unsigned) -> Self;
-    fn add_unsigned(self, other: Self::Unsigned) -> Self;
-}
-
-impl SignedUnsigned for i32 {
-    type Unsigned = u32;
-    #[inline(always)]
-    fn sub_unsigned(self, other: u32) -> Self {
-        if cfg!(debug_assertions) {
-            self.checked_sub_unsigned(other).unwrap()
-        } else {
-            self.wrapping_sub_unsigned(other)
-        }
-    }
-
-    #[inline(always)]
-    fn add_unsigned(self, other: Self::Unsigned) -> Self {
-        if cfg!(debug_assertions) {
-            self.checked_add_unsigned(other).unwrap()
-        } else {
-            self.wrapping_add_unsigned(other)
-        }
-    }
+    i64::from(point.x) * 4_000_000 + i64::from(point.y)
 }
 
-super::day_test! {part_1 == 4748135}
-super::day_test! {part_2 == 13743542639657}
+super::day_test! {part_1}
+super::day_test! {part_2}
 
 #[test]
 fn test_demo_1() {
     let input = super::day_test!(@demo_input);
-    let input = generator(&input);
+    let input = Day::parse(&input).unwrap();
     assert_eq!(count_non_beacons_at_y(&input, 10), 26);
 }
 
 #[test]
 fn test_demo_2() {
     let input = super::day_test!(@demo_input);
-    let input = generator(&input);
-    assert_eq!(find_empty_spot::<20>(&input), (14, 11));
+    let input = Day::parse(&input).unwrap();
+    assert_eq!(find_empty_spot::<20>(&input), Point::new(14, 11));
     assert_eq!(empty_spot_frequency::<20>(&input), 56000011);
 }
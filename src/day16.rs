@@ -1,3 +1,5 @@
+use crate::parse::{ParseError, Parser};
+use crate::Solution;
 use ahash::{HashMap, HashMapExt, HashSet};
 use bitvec::prelude::*;
 use core::fmt;
@@ -82,41 +84,86 @@ impl IndexMut<Idx> for Input {
     }
 }
 
-pub fn generator(s: &str) -> Input {
-    let mut nodes = Vec::<(u8, ValveName, Vec<ValveName>)>::with_capacity(64);
-    for line in s.lines() {
-        let line = line.strip_prefix("Valve ").unwrap();
-        let valve_name = ValveName::from_bytes(line.as_bytes());
-        let line = &line[2..];
-        let line = line.strip_prefix(" has flow rate=").unwrap();
-        let (rate, line) = line
-            .split_once("; tunnels lead to valves ")
-            .or_else(|| line.split_once("; tunnel leads to valve "))
-            .unwrap();
-        let flow: u8 = rate.parse().unwrap();
-        let links = line
-            .split(", ")
-            .map(|n| ValveName::from_bytes(n.as_bytes()));
-
-        nodes.push((flow, valve_name, links.collect()));
-    }
-    nodes.sort_unstable_by_key(|n| cmp::Reverse(n.0));
-    let mut input = Input::default();
-    let mut node_idx_by_name = HashMap::with_capacity(64);
-    for &(flow, name, _) in &nodes {
-        node_idx_by_name.insert(name, input.add_node(Node { flow }));
-    }
-    for (i, (_flow, _name, links)) in nodes.iter().enumerate() {
-        let idx = i as Idx;
-        for link in links {
-            input.add_edge(idx, node_idx_by_name[link]);
+pub struct Day;
+
+impl Solution for Day {
+    type Input<'a> = Input;
+    type Answer1 = u16;
+    type Answer2 = u16;
+
+    fn parse(s: &str) -> Result<Self::Input<'_>, ParseError> {
+        let mut nodes = Vec::<(u8, ValveName, Vec<ValveName>)>::with_capacity(64);
+        let p = Parser::new(s);
+        for line in s.lines() {
+            let line = p.strip_prefix(line, "Valve ")?;
+            let (valve_name, line) = p.split_once(line, " has flow rate=")?;
+            let valve_name = ValveName::parse(&p, valve_name)?;
+            let (rate, line) = line
+                .split_once("; tunnels lead to valves ")
+                .or_else(|| line.split_once("; tunnel leads to valve "))
+                .ok_or_else(|| p.error_after(line, "\"; tunnels lead to valves \""))?;
+            let flow: u8 = p.number(rate)?;
+            let links = line.split(", ").map(|n| ValveName::parse(&p, n));
+
+            nodes.push((flow, valve_name, links.collect::<Result<_, _>>()?));
         }
+        nodes.sort_unstable_by_key(|n| cmp::Reverse(n.0));
+        let mut input = Input::default();
+        let mut node_idx_by_name = HashMap::with_capacity(64);
+        for &(flow, name, _) in &nodes {
+            node_idx_by_name.insert(name, input.add_node(Node { flow }));
+        }
+        for (i, (_flow, _name, links)) in nodes.iter().enumerate() {
+            let idx = i as Idx;
+            for link in links {
+                let &link_idx = node_idx_by_name.get(link).ok_or_else(|| {
+                    p.error_after(s, format!("a line for valve {link:?}, which has a tunnel to it"))
+                })?;
+                input.add_edge(idx, link_idx);
+            }
+        }
+        input.start = *node_idx_by_name
+            .get(&ValveName(b'A', b'A'))
+            .ok_or_else(|| p.error_after(s, "a line for the starting valve, \"AA\""))?;
+
+        input.floyd_warshall();
+
+        Ok(input)
     }
-    input.start = node_idx_by_name[&ValveName(b'A', b'A')];
 
-    input.floyd_warshall();
+    fn part_1(input: &Self::Input<'_>) -> u16 {
+        let mut max_pressure = 0;
+        pressure_releases(input, 30, |_, pressure| {
+            max_pressure = max_pressure.max(pressure)
+        });
+        max_pressure
+    }
 
-    input
+    fn part_2(input: &Self::Input<'_>) -> u16 {
+        let mut max_pressures: HashMap<NodeSet, u16> = HashMap::with_capacity(4096);
+        pressure_releases(input, 26, |nodes, pressure| {
+            let dst = max_pressures.entry(nodes).or_default();
+            *dst = (*dst).max(pressure);
+        });
+
+        let mut max_pressures: Vec<(NodeSet, u16)> = max_pressures.into_iter().collect();
+        max_pressures.sort_unstable_by_key(|&(_, pressure)| cmp::Reverse(pressure));
+
+        let mut max_pressure = 0;
+        for (i, &(my_nodes, my_pressure)) in max_pressures.iter().enumerate() {
+            for &(elephant_nodes, elephant_pressure) in &max_pressures[i + 1..] {
+                // Pressures are sorted in decreasing pressures, if this can't get enough pressure to beat the max,
+                // no further one will either
+                if my_pressure + elephant_pressure < max_pressure {
+                    break;
+                }
+                if (my_nodes & elephant_nodes).not_any() {
+                    max_pressure = max_pressure.max(my_pressure + elephant_pressure);
+                }
+            }
+        }
+        max_pressure
+    }
 }
 
 type NodeSet = BitArr!(for 16);
@@ -171,46 +218,15 @@ where
     }
 }
 
-pub fn part_1(input: &Input) -> u16 {
-    let mut max_pressure = 0;
-    pressure_releases(input, 30, |_, pressure| {
-        max_pressure = max_pressure.max(pressure)
-    });
-    max_pressure
-}
-
-pub fn part_2(input: &Input) -> u16 {
-    let mut max_pressures: HashMap<NodeSet, u16> = HashMap::with_capacity(4096);
-    pressure_releases(input, 26, |nodes, pressure| {
-        let dst = max_pressures.entry(nodes).or_default();
-        *dst = (*dst).max(pressure);
-    });
-
-    let mut max_pressures: Vec<(NodeSet, u16)> = max_pressures.into_iter().collect();
-    max_pressures.sort_unstable_by_key(|&(_, pressure)| cmp::Reverse(pressure));
-
-    let mut max_pressure = 0;
-    for (i, &(my_nodes, my_pressure)) in max_pressures.iter().enumerate() {
-        for &(elephant_nodes, elephant_pressure) in &max_pressures[i + 1..] {
-            // Pressures are sorted in decreasing pressures, if this can't get enough pressure to beat the max,
-            // no further one will either
-            if my_pressure + elephant_pressure < max_pressure {
-                break;
-            }
-            if (my_nodes & elephant_nodes).not_any() {
-                max_pressure = max_pressure.max(my_pressure + elephant_pressure);
-            }
-        }
-    }
-    max_pressure
-}
-
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 struct ValveName(u8, u8);
 
 impl ValveName {
-    fn from_bytes(bytes: &[u8]) -> Self {
-        Self(bytes[0], bytes[1])
+    fn parse(p: &Parser, s: &str) -> Result<Self, ParseError> {
+        match *s.as_bytes() {
+            [a, b] if a.is_ascii_uppercase() && b.is_ascii_uppercase() => Ok(Self(a, b)),
+            _ => Err(p.error(s, "a valve name, like \"AA\"")),
+        }
     }
 }
 
@@ -222,7 +238,7 @@ impl fmt::Debug for ValveName {
     }
 }
 
-super::day_test! {demo_1 == 1651}
-super::day_test! {demo_2 == 1707}
-super::day_test! {part_1 == 1862}
-super::day_test! {part_2 == 2422}
+super::day_test! {demo_1}
+super::day_test! {demo_2}
+super::day_test! {part_1}
+super::day_test! {part_2}
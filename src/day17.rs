@@ -1,8 +1,8 @@
+use crate::grid::BitGrid;
+use crate::parse::ParseError;
+use crate::Solution;
 use ahash::{HashMap, HashMapExt};
-use bitvec::prelude::*;
 use std::collections::hash_map::Entry;
-use std::fmt;
-use std::fmt::Write;
 
 const COLUMNS: usize = 7;
 
@@ -28,7 +28,7 @@ impl Shape {
         }
     }
 
-    fn width(self) -> u32 {
+    fn width(self) -> usize {
         match self {
             Shape::Minus => 4,
             Shape::Plus => 3,
@@ -38,7 +38,17 @@ impl Shape {
         }
     }
 
-    const fn offsets(self) -> &'static [(u32, u32)] {
+    fn height(self) -> usize {
+        match self {
+            Shape::Minus => 1,
+            Shape::Plus => 3,
+            Shape::L => 3,
+            Shape::I => 4,
+            Shape::Square => 2,
+        }
+    }
+
+    const fn offsets(self) -> &'static [(usize, usize)] {
         match self {
             Shape::Minus => &[(0, 0), (1, 0), (2, 0), (3, 0)],
             Shape::Plus => &[(1, 0), (0, 1), (1, 1), (2, 1), (1, 2)],
@@ -48,66 +58,106 @@ impl Shape {
         }
     }
 
-    fn hit_test(self, x: u32, y: u32, board: &Board) -> bool {
+    fn hit_test(self, x: usize, y: usize, board: &BitGrid) -> bool {
         for &(dx, dy) in self.offsets() {
-            if board.get(x + dx, y + dy) {
+            // Above the board is empty
+            if board.get(x + dx, y + dy) == Some(true) {
                 return true;
             }
         }
         false
     }
 
-    fn set(self, x: u32, y: u32, board: &mut Board) {
-        for &(dx, dy) in self.offsets().iter().rev() {
-            board.set(x + dx, y + dy)
+    fn set(self, x: usize, y: usize, board: &mut BitGrid) {
+        if y + self.height() > board.height() {
+            board.resize(y + self.height());
+        }
+        for &(dx, dy) in self.offsets() {
+            board.set(x + dx, y + dy, true);
         }
     }
 }
 
-#[derive(Default, Clone, PartialEq, Eq, Hash)]
-pub struct Board {
-    bits: BitVec,
-}
+pub struct Day;
 
-fn idx(x: u32, y: u32) -> usize {
-    let x: usize = x.try_into().unwrap();
-    let y: usize = y.try_into().unwrap();
-    debug_assert!(x < COLUMNS);
+impl Solution for Day {
+    type Input<'a> = &'a str;
+    type Answer1 = usize;
+    type Answer2 = u64;
 
-    y * COLUMNS + x
-}
-
-impl Board {
-    fn get(&self, x: u32, y: u32) -> bool {
-        self.bits.get(idx(x, y)).map_or(false, |bit| *bit)
+    fn parse(s: &str) -> Result<Self::Input<'_>, ParseError> {
+        Ok(s.trim_end())
     }
 
-    fn set(&mut self, x: u32, y: u32) {
-        let idx = idx(x, y);
-        if idx >= self.bits.len() {
-            self.bits.resize(idx + 1, false);
+    fn part_1(s: &Self::Input<'_>) -> usize {
+        let mut game = Game::default();
+        let jets = Jets(s.as_bytes());
+
+        for _ in 0..2022 {
+            game.drop(jets);
         }
-        self.bits.set(idx, true);
-    }
 
-    fn height(&self) -> u32 {
-        ((self.bits.len() + (COLUMNS - 1)) / COLUMNS)
-            .try_into()
-            .unwrap()
+        game.board.height()
     }
-}
 
-pub fn generator(s: &str) -> &str {
-    s.trim_end()
+    fn part_2(s: &Self::Input<'_>) -> u64 {
+        const ROCK_COUNT: u64 = 1_000_000_000_000;
+
+        let mut game = Game::default();
+        let jets = Jets(s.as_bytes());
+
+        let mut i = 500;
+        for _ in 0..i {
+            game.drop(jets);
+        }
+
+        let mut games = HashMap::with_capacity(2048);
+
+        let (loop_len, loop_height) = loop {
+            let key = (
+                game.piece,
+                game.jet_idx,
+                game.board.bits()[(game.board.height() - 50) * COLUMNS..].to_bitvec(),
+            );
+            match games.entry(key) {
+                Entry::Occupied(e) => {
+                    let &(old_i, old_height) = e.get();
+                    break (i - old_i, game.board.height() - old_height);
+                }
+                Entry::Vacant(e) => {
+                    e.insert((i, game.board.height()));
+                }
+            }
+            game.drop(jets);
+            i += 1;
+        };
+
+        let extra_after_loops = (ROCK_COUNT - i) % loop_len;
+        for _ in 0..extra_after_loops {
+            game.drop(jets);
+        }
+
+        game.board.height() as u64 + ((ROCK_COUNT - i) / loop_len) * loop_height as u64
+    }
 }
 
-#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 struct Game {
-    board: Board,
+    board: BitGrid,
     piece: Shape,
     jet_idx: u32,
 }
 
+impl Default for Game {
+    fn default() -> Self {
+        Self {
+            board: BitGrid::new(COLUMNS, 0),
+            piece: Shape::default(),
+            jet_idx: 0,
+        }
+    }
+}
+
 #[derive(Copy, Clone)]
 struct Jets<'a>(&'a [u8]);
 
@@ -133,18 +183,18 @@ impl Game {
         let piece = self.piece;
         self.piece = piece.next();
 
-        let mut x = 2u32;
+        let mut x = 2usize;
 
         for _ in 0..3 {
             let new_x = x.saturating_add_signed(jets.next_x(&mut self.jet_idx).into());
-            x = new_x.min(COLUMNS as u32 - piece.width());
+            x = new_x.min(COLUMNS - piece.width());
             debug_assert!(!piece.hit_test(x, self.board.height(), &self.board));
         }
 
-        let mut move_wind = |x: &mut u32, y: u32| {
+        let mut move_wind = |x: &mut usize, y: usize| {
             let new_x = x
                 .checked_add_signed(jets.next_x(&mut self.jet_idx).into())
-                .filter(|&i| i <= COLUMNS as u32 - piece.width());
+                .filter(|&i| i <= COLUMNS - piece.width());
             if let Some(new_x) = new_x {
                 if !piece.hit_test(new_x, y, &self.board) {
                     *x = new_x;
@@ -165,73 +215,7 @@ impl Game {
     }
 }
 
-pub fn part_1(s: &str) -> u32 {
-    let mut game = Game::default();
-    let jets = Jets(s.as_bytes());
-
-    for _ in 0..2022 {
-        game.drop(jets);
-    }
-
-    game.board.height()
-}
-
-pub fn part_2(s: &str) -> u64 {
-    const ROCK_COUNT: u64 = 1_000_000_000_000;
-
-    let mut game = Game::default();
-    let jets = Jets(s.as_bytes());
-
-    let mut i = 500;
-    for _ in 0..i {
-        game.drop(jets);
-    }
-
-    let mut games = HashMap::with_capacity(2048);
-
-    let (loop_len, loop_height) = loop {
-        let key = (
-            game.piece,
-            game.jet_idx,
-            game.board.bits[game.board.bits.len() - (50 * COLUMNS)..].to_bitvec(),
-        );
-        match games.entry(key) {
-            Entry::Occupied(e) => {
-                let &(old_i, old_height) = e.get();
-                break (i - old_i, game.board.height() - old_height);
-            }
-            Entry::Vacant(e) => {
-                e.insert((i, game.board.height()));
-            }
-        }
-        game.drop(jets);
-        i += 1;
-    };
-
-    let extra_after_loops = (ROCK_COUNT - i) % loop_len;
-    for _ in 0..extra_after_loops {
-        game.drop(jets);
-    }
-
-    game.board.height() as u64 + ((ROCK_COUNT - i) / loop_len) * u64::from(loop_height)
-}
-
-impl fmt::Debug for Board {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        // let min_y = self.height().saturating_sub(8);
-        let min_y = 0;
-        for y in (min_y..self.height()).rev() {
-            f.write_char('\n')?;
-            for x in 0..COLUMNS {
-                let ch = if self.get(x as u32, y) { '#' } else { '.' };
-                f.write_char(ch)?;
-            }
-        }
-        Ok(())
-    }
-}
-
-super::day_test! {demo_1 == 3068}
-super::day_test! {demo_2 == 1514285714288}
-super::day_test! {part_1 == 3149}
-super::day_test! {part_2 == 1553982300884}
+super::day_test! {demo_1}
+super::day_test! {demo_2}
+super::day_test! {part_1}
+super::day_test! {part_2}
@@ -1,138 +1,147 @@
-use bitvec::prelude::*;
-use std::str::FromStr;
+use crate::geom::Point3;
+use crate::grid::BitGrid;
+use crate::parse::{ParseError, Parser};
+use crate::Solution;
 
-type Pos = [usize; 3];
+type Pos = Point3<usize>;
 
+/// The cubes, as the layers of each `z` stacked in a grid, row `z * y_len + y`
 #[derive(Debug)]
 pub struct Field {
-    x_len: usize,
     y_len: usize,
-    bits: BitVec,
+    layers: BitGrid,
 }
 
 impl Field {
-    pub fn z_len(&self) -> usize {
-        self.bits.len() / (self.x_len * self.y_len)
+    fn x_len(&self) -> usize {
+        self.layers.width()
     }
 
-    fn idx(&self, [x, y, z]: Pos) -> usize {
-        debug_assert!(x < self.x_len);
-        debug_assert!(y < self.y_len);
-        z * self.x_len * self.y_len + y * self.x_len + x
+    fn z_len(&self) -> usize {
+        self.layers.height() / self.y_len
     }
 
-    fn pos(&self, idx: usize) -> Pos {
-        let x = idx % self.x_len;
-        let y = idx / self.x_len % self.y_len;
-        let z = idx / (self.x_len * self.y_len);
-        [x, y, z]
+    fn cell(&self, Point3 { x, y, z }: Pos) -> (usize, usize) {
+        debug_assert!(y < self.y_len);
+        (x, z * self.y_len + y)
     }
 
     fn get(&self, pos: Pos) -> bool {
-        self.bits[self.idx(pos)]
+        self.layers[self.cell(pos)]
     }
-}
 
-pub fn generator(s: &str) -> Field {
-    let mut positions = Vec::with_capacity(1024);
-    let mut max = [0; 3];
-    for line in s.lines() {
-        let mut items = line.split(',').map(|n| usize::from_str(n).unwrap());
-        let x = items.next().unwrap();
-        let y = items.next().unwrap();
-        let z = items.next().unwrap();
+    fn cubes(&self) -> impl Iterator<Item = Pos> + '_ {
+        self.layers
+            .iter_ones()
+            .map(|(x, row)| Pos::new(x, row % self.y_len, row / self.y_len))
+    }
+}
 
-        // Shift everything, so we have some free space at the beginning
-        let point = [x + 1, y + 1, z + 1];
+pub struct Day;
+
+impl Solution for Day {
+    type Input<'a> = Field;
+    type Answer1 = u32;
+    type Answer2 = u32;
+
+    fn parse(s: &str) -> Result<Self::Input<'_>, ParseError> {
+        let p = Parser::new(s);
+        let mut positions = Vec::with_capacity(1024);
+        let mut max = Pos::default();
+        for line in s.lines() {
+            let mut items = line.split(',');
+            let mut coord = || -> Result<usize, ParseError> {
+                p.number(p.next(&mut items, line, "a coordinate")?)
+            };
+            let x = coord()?;
+            let y = coord()?;
+            let z = coord()?;
+
+            // Shift everything, so we have some free space at the beginning
+            let point = Pos::new(x + 1, y + 1, z + 1);
 
-        max.iter_mut()
-            .zip(point)
             // Include an extra space on the right side as well
-            .for_each(|(m, new)| *m = (*m).max(new + 2));
+            max = Pos::new(
+                max.x.max(point.x + 2),
+                max.y.max(point.y + 2),
+                max.z.max(point.z + 2),
+            );
 
-        positions.push(point);
+            positions.push(point);
+        }
+        if positions.is_empty() {
+            return Err(p.error(s, "a cube, \"x,y,z\""));
+        }
+        let mut field = Field {
+            y_len: max.y,
+            layers: BitGrid::new(max.x, max.y * max.z),
+        };
+        positions.iter().for_each(|&pos| {
+            let (x, row) = field.cell(pos);
+            field.layers.set(x, row, true);
+        });
+        Ok(field)
     }
-    let bits = bitvec![0; max[0] * max[1] * max[2]];
-    let mut field = Field {
-        x_len: max[0],
-        y_len: max[1],
-        bits,
-    };
-    positions.iter().for_each(|&pos| {
-        let idx = field.idx(pos);
-        field.bits.set(idx, true);
-    });
-    field
-}
-
-const ALL_DIRECTIONS: [[isize; 3]; 6] = [
-    [-1, 0, 0],
-    [1, 0, 0],
-    [0, -1, 0],
-    [0, 1, 0],
-    [0, 0, -1],
-    [0, 0, 1],
-];
 
-pub fn part_1(field: &Field) -> u32 {
-    let mut surface_area = 0;
-    for idx in field.bits.iter_ones() {
-        let [x, y, z] = field.pos(idx);
-        for [dx, dy, dz] in ALL_DIRECTIONS {
-            let new_x = x.add_signed(dx);
-            let new_y = y.add_signed(dy);
-            let new_z = z.add_signed(dz);
-
-            if !field.get([new_x, new_y, new_z]) {
-                surface_area += 1;
+    fn part_1(field: &Self::Input<'_>) -> u32 {
+        let mut surface_area = 0;
+        for pos in field.cubes() {
+            for dir in ALL_DIRECTIONS {
+                if !field.get(pos.add_signed(dir)) {
+                    surface_area += 1;
+                }
             }
         }
+        surface_area
     }
-    surface_area
-}
 
-pub fn part_2(field: &Field) -> u32 {
-    let mut queue: Vec<Pos> = Vec::with_capacity(1024);
-    queue.push([0, 0, 0]);
-    let mut visited = bitvec![0; field.bits.len()];
-
-    let mut surface_area = 0;
-
-    while let Some([x, y, z]) = queue.pop() {
-        for [dx, dy, dz] in ALL_DIRECTIONS {
-            let Some(new_x) = x.checked_add_signed(dx).filter(|&i| i < field.x_len) else { continue };
-            let Some(new_y) = y.checked_add_signed(dy).filter(|&i| i < field.y_len) else { continue };
-            let Some(new_z) = z.checked_add_signed(dz).filter(|&i| i < field.z_len()) else { continue };
-
-            let new_pos = [new_x, new_y, new_z];
-            let idx = field.idx(new_pos);
-            if field.get(new_pos) {
-                surface_area += 1;
-            } else if !visited[idx] {
-                visited.set(idx, true);
-                queue.push(new_pos);
+    fn part_2(field: &Self::Input<'_>) -> u32 {
+        let mut queue: Vec<Pos> = Vec::with_capacity(1024);
+        queue.push(Pos::default());
+        let mut visited = BitGrid::new(field.x_len(), field.layers.height());
+
+        let mut surface_area = 0;
+
+        let (x_len, z_len) = (field.x_len(), field.z_len());
+        while let Some(pos) = queue.pop() {
+            for dir in ALL_DIRECTIONS {
+                let Some(new_pos) = pos.checked_add_signed(dir).filter(|new_pos| {
+                    new_pos.x < x_len && new_pos.y < field.y_len && new_pos.z < z_len
+                }) else {
+                    continue;
+                };
+                let (cell_x, cell_row) = field.cell(new_pos);
+                if field.get(new_pos) {
+                    surface_area += 1;
+                } else if !visited[(cell_x, cell_row)] {
+                    visited.set(cell_x, cell_row, true);
+                    queue.push(new_pos);
+                }
             }
         }
-    }
 
-    surface_area
+        surface_area
+    }
 }
 
-trait UnsignedSigned {
-    type Signed;
-    fn add_signed(self, other: Self::Signed) -> Self;
-}
+const ALL_DIRECTIONS: [Point3<isize>; 6] = [
+    Point3::new(-1, 0, 0),
+    Point3::new(1, 0, 0),
+    Point3::new(0, -1, 0),
+    Point3::new(0, 1, 0),
+    Point3::new(0, 0, -1),
+    Point3::new(0, 0, 1),
+];
 
-impl UnsignedSigned for usize {
-    type Signed = isize;
+super::day_test! {demo_1}
 
-    fn add_signed(self, other: Self::Signed) -> Self {
-        if cfg!(debug_assertions) {
-            self.checked_add_signed(other).unwrap()
-        } else {
-            self.wrapping_add_signed(other)
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn no_cubes() {
+        let err = Day::parse("").unwrap_err();
+        assert_eq!(err.to_string(), "1:1: expected a cube, \"x,y,z\"");
     }
 }
-
-super::day_test! {demo_1 == 64}
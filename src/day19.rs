@@ -1,3 +1,5 @@
+use crate::parse::{ParseError, Parser};
+use crate::Solution;
 use std::cmp;
 
 pub struct Blueprint {
@@ -9,78 +11,71 @@ pub struct Blueprint {
     geode_robot_obsidian_cost: u32,
 }
 
-pub fn generator(s: &str) -> Vec<Blueprint> {
-    let mut result = Vec::with_capacity(1024);
-    for line in s.lines() {
-        // Skip until colon, like `Blueprint 1: `
-        let (_blueprint_num, line) = line.split_once(": ").unwrap();
-        let (ore_robot_ore_cost, line) = line
-            .strip_prefix("Each ore robot costs ")
-            .unwrap()
-            .split_once(" ")
-            .unwrap();
-        let (clay_robot_ore_cost, line) = line
-            .strip_prefix("ore. Each clay robot costs ")
-            .unwrap()
-            .split_once(" ")
-            .unwrap();
-        let (obsidian_robot_ore_cost, line) = line
-            .strip_prefix("ore. Each obsidian robot costs ")
-            .unwrap()
-            .split_once(" ")
-            .unwrap();
-        let (obsidian_robot_clay_cost, line) = line
-            .strip_prefix("ore and ")
-            .unwrap()
-            .split_once(" ")
-            .unwrap();
-        let (geode_robot_ore_cost, line) = line
-            .strip_prefix("clay. Each geode robot costs ")
-            .unwrap()
-            .split_once(" ")
-            .unwrap();
-        let (geode_robot_obsidian_cost, line) = line
-            .strip_prefix("ore and ")
-            .unwrap()
-            .split_once(" ")
-            .unwrap();
-        assert_eq!(line, "obsidian.");
-
-        result.push(Blueprint {
-            ore_robot_ore_cost: ore_robot_ore_cost.parse().unwrap(),
-            clay_robot_ore_cost: clay_robot_ore_cost.parse().unwrap(),
-            obsidian_robot_ore_cost: obsidian_robot_ore_cost.parse().unwrap(),
-            obsidian_robot_clay_cost: obsidian_robot_clay_cost.parse().unwrap(),
-            geode_robot_ore_cost: geode_robot_ore_cost.parse().unwrap(),
-            geode_robot_obsidian_cost: geode_robot_obsidian_cost.parse().unwrap(),
-        })
+pub struct Day;
+
+impl Solution for Day {
+    type Input<'a> = Vec<Blueprint>;
+    type Answer1 = u32;
+    type Answer2 = u32;
+
+    fn parse(s: &str) -> Result<Self::Input<'_>, ParseError> {
+        let p = Parser::new(s);
+        // The number after `prefix`, and the rest of the line after it
+        let cost = |line, prefix| -> Result<_, ParseError> {
+            let (cost, line) = p.split_once(p.strip_prefix(line, prefix)?, " ")?;
+            Ok((p.number(cost)?, line))
+        };
+
+        let mut result = Vec::with_capacity(1024);
+        for line in s.lines() {
+            // Skip until colon, like `Blueprint 1: `
+            let (_blueprint_num, line) = p.split_once(line, ": ")?;
+            let (ore_robot_ore_cost, line) = cost(line, "Each ore robot costs ")?;
+            let (clay_robot_ore_cost, line) = cost(line, "ore. Each clay robot costs ")?;
+            let (obsidian_robot_ore_cost, line) = cost(line, "ore. Each obsidian robot costs ")?;
+            let (obsidian_robot_clay_cost, line) = cost(line, "ore and ")?;
+            let (geode_robot_ore_cost, line) = cost(line, "clay. Each geode robot costs ")?;
+            let (geode_robot_obsidian_cost, line) = cost(line, "ore and ")?;
+            if line != "obsidian." {
+                return Err(p.error(line, "\"obsidian.\""));
+            }
+
+            result.push(Blueprint {
+                ore_robot_ore_cost,
+                clay_robot_ore_cost,
+                obsidian_robot_ore_cost,
+                obsidian_robot_clay_cost,
+                geode_robot_ore_cost,
+                geode_robot_obsidian_cost,
+            })
+        }
+        Ok(result)
     }
-    result
-}
 
-pub fn part_1(blueprints: &[Blueprint]) -> u32 {
-    const MINUTES: u32 = 24;
+    fn part_1(blueprints: &Self::Input<'_>) -> u32 {
+        const MINUTES: u32 = 24;
+
+        let mut result = 0;
+        for (i, blueprint) in blueprints.iter().enumerate() {
+            let id_num = u32::try_from(i + 1).unwrap();
+            let best_score = best_blueprint_score(blueprint, MINUTES);
+            result += id_num * best_score;
+        }
 
-    let mut result = 0;
-    for (i, blueprint) in blueprints.iter().enumerate() {
-        let id_num = u32::try_from(i + 1).unwrap();
-        let best_score = best_blueprint_score(blueprint, MINUTES);
-        result += id_num * u32::from(best_score);
+        result
     }
 
-    result
-}
+    fn part_2(blueprints: &Self::Input<'_>) -> u32 {
+        const MINUTES: u32 = 32;
 
-pub fn part_2(blueprints: &[Blueprint]) -> u32 {
-    const MINUTES: u32 = 32;
+        let mut result = 1;
+        for blueprint in blueprints.iter().take(3) {
+            let best_score = best_blueprint_score(blueprint, MINUTES);
+            result *= best_score;
+        }
 
-    let mut result = 1;
-    for blueprint in blueprints.iter().take(3) {
-        let best_score = best_blueprint_score(blueprint, MINUTES);
-        result *= u32::from(best_score);
+        result
     }
-
-    result
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
@@ -149,7 +144,7 @@ fn best_blueprint_score(blueprint: &Blueprint, minutes: u32) -> u32 {
 
         // Quick exit if building geodes every minute can't even beat best
         if state.geode.count
-            + (state.geode.rate + (state.minutes_left + 1) / 2) * state.minutes_left
+            + (state.geode.rate + state.minutes_left.div_ceil(2)) * state.minutes_left
             < best_score
         {
             continue;
@@ -157,9 +152,10 @@ fn best_blueprint_score(blueprint: &Blueprint, minutes: u32) -> u32 {
 
         if state.ore.rate < most_needed_ore {
             // Try building an ore robot
-            let next_ore = (blueprint.ore_robot_ore_cost.saturating_sub(state.ore.count)
-                + (state.ore.rate - 1))
-                / state.ore.rate;
+            let next_ore = blueprint
+                .ore_robot_ore_cost
+                .saturating_sub(state.ore.count)
+                .div_ceil(state.ore.rate);
             if next_ore < state.minutes_left {
                 let mut new_state = state.after_minutes(next_ore + 1);
                 new_state.ore.count -= blueprint.ore_robot_ore_cost;
@@ -170,11 +166,10 @@ fn best_blueprint_score(blueprint: &Blueprint, minutes: u32) -> u32 {
 
         if state.clay.rate < most_needed_clay {
             // Try building a clay robot
-            let next_clay = (blueprint
+            let next_clay = blueprint
                 .clay_robot_ore_cost
                 .saturating_sub(state.ore.count)
-                + (state.ore.rate - 1))
-                / state.ore.rate;
+                .div_ceil(state.ore.rate);
             if next_clay < state.minutes_left {
                 let mut new_state = state.after_minutes(next_clay + 1);
                 new_state.ore.count -= blueprint.clay_robot_ore_cost;
@@ -185,16 +180,14 @@ fn best_blueprint_score(blueprint: &Blueprint, minutes: u32) -> u32 {
 
         if state.obsidian.rate < most_needed_obsidian && state.clay.rate > 0 {
             let next_obsidian = cmp::max(
-                (blueprint
+                blueprint
                     .obsidian_robot_ore_cost
                     .saturating_sub(state.ore.count)
-                    + (state.ore.rate - 1))
-                    / state.ore.rate,
-                (blueprint
+                    .div_ceil(state.ore.rate),
+                blueprint
                     .obsidian_robot_clay_cost
                     .saturating_sub(state.clay.count)
-                    + (state.clay.rate - 1))
-                    / state.clay.rate,
+                    .div_ceil(state.clay.rate),
             );
             if next_obsidian < state.minutes_left {
                 let mut new_state = state.after_minutes(next_obsidian + 1);
@@ -207,16 +200,14 @@ fn best_blueprint_score(blueprint: &Blueprint, minutes: u32) -> u32 {
         // Try building a geode robot
         if state.obsidian.rate > 0 {
             let next_geode = cmp::max(
-                (blueprint
+                blueprint
                     .geode_robot_ore_cost
                     .saturating_sub(state.ore.count)
-                    + (state.ore.rate - 1))
-                    / state.ore.rate,
-                (blueprint
+                    .div_ceil(state.ore.rate),
+                blueprint
                     .geode_robot_obsidian_cost
                     .saturating_sub(state.obsidian.count)
-                    + (state.obsidian.rate - 1))
-                    / state.obsidian.rate,
+                    .div_ceil(state.obsidian.rate),
             );
             if next_geode < state.minutes_left {
                 let mut new_state = state.after_minutes(next_geode + 1);
@@ -231,7 +222,7 @@ fn best_blueprint_score(blueprint: &Blueprint, minutes: u32) -> u32 {
     best_score
 }
 
-super::day_test! {demo_1 == 33}
-super::day_test! {demo_2 == 3472}
-super::day_test! {part_1 == 1150}
-super::day_test! {part_2 == 37367}
+super::day_test! {demo_1}
+super::day_test! {demo_2}
+super::day_test! {part_1}
+super::day_test! {part_2}
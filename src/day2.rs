@@ -1,3 +1,6 @@
+use crate::parse::{ParseError, Parser};
+use crate::Solution;
+
 #[derive(Copy, Clone, Debug)]
 #[repr(u8)]
 pub enum RPS {
@@ -83,13 +86,46 @@ impl Round {
     }
 }
 
-pub fn generator(s: &str) -> Vec<Round> {
-    assert_eq!(s.len() % 4, 0);
-    let mut result = Vec::with_capacity(1024);
-    s.as_bytes()
-        .chunks_exact(4)
-        .for_each(|chunk| result.push(Round::from_abc_xyz(chunk[0], chunk[2])));
-    result
+pub struct Day;
+
+impl Solution for Day {
+    type Input<'a> = Vec<Round>;
+    type Answer1 = u32;
+    type Answer2 = u32;
+
+    fn parse(s: &str) -> Result<Self::Input<'_>, ParseError> {
+        let p = Parser::new(s);
+        let mut result = Vec::with_capacity(1024);
+        for (i, chunk) in s.as_bytes().chunks(4).enumerate() {
+            match *chunk {
+                [abc @ b'A'..=b'C', b' ', xyz @ b'X'..=b'Z', b'\n'] => {
+                    result.push(Round::from_abc_xyz(abc, xyz))
+                }
+                _ => return Err(p.error(&s[i * 4..], "a line like \"A Y\"")),
+            }
+        }
+        Ok(result)
+    }
+
+    fn part_1(input: &Self::Input<'_>) -> u32 {
+        input
+            .iter()
+            .copied()
+            .map(|round| score(round.right(), round.left()))
+            .sum()
+    }
+
+    fn part_2(input: &Self::Input<'_>) -> u32 {
+        input
+            .iter()
+            .copied()
+            .map(|round| {
+                let outcome = round.outcome();
+                let mine = round.left().play_to(outcome);
+                u32::from(mine.value()) + u32::from(outcome as u8)
+            })
+            .sum()
+    }
 }
 
 fn score(mine: RPS, yours: RPS) -> u32 {
@@ -104,27 +140,7 @@ fn score(mine: RPS, yours: RPS) -> u32 {
     value + u32::from(outcome as u8)
 }
 
-pub fn part_1(input: &[Round]) -> u32 {
-    input
-        .iter()
-        .copied()
-        .map(|round| score(round.right(), round.left()))
-        .sum()
-}
-
-pub fn part_2(input: &[Round]) -> u32 {
-    input
-        .iter()
-        .copied()
-        .map(|round| {
-            let outcome = round.outcome();
-            let mine = round.left().play_to(outcome);
-            u32::from(mine.value()) + u32::from(outcome as u8)
-        })
-        .sum()
-}
-
-super::day_test! {demo_1 == 15}
-super::day_test! {demo_2 == 12}
-super::day_test! {part_1 == 11841}
-super::day_test! {part_2 == 13022}
+super::day_test! {demo_1}
+super::day_test! {demo_2}
+super::day_test! {part_1}
+super::day_test! {part_2}
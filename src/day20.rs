@@ -1,32 +1,46 @@
-pub fn generator(s: &str) -> Vec<i64> {
-    let mut result = Vec::with_capacity(1024);
-    result.extend(s.lines().map(|l| l.parse::<i64>().unwrap()));
-    result
-}
+use crate::parse::{ParseError, Parser};
+use crate::Solution;
+
+pub struct Day;
 
-pub fn part_1(items: &[i64]) -> i64 {
-    let mixed = mix(items, 1);
-    let start_idx = mixed.iter().position(|&item| item == 0).unwrap();
+impl Solution for Day {
+    type Input<'a> = Vec<i64>;
+    type Answer1 = i64;
+    type Answer2 = i64;
 
-    let mut sum = 0;
-    for pos in POSITIONS {
-        sum += mixed[(pos + start_idx) % mixed.len()];
+    fn parse(s: &str) -> Result<Self::Input<'_>, ParseError> {
+        let p = Parser::new(s);
+        let mut result = Vec::with_capacity(1024);
+        for line in s.lines() {
+            result.push(p.number(line)?);
+        }
+        Ok(result)
     }
-    sum
-}
 
-pub fn part_2(items: &[i64]) -> i64 {
-    const DECRYPTION_KEY: i64 = 811_589_153;
-    let items: Vec<i64> = items.iter().map(|&item| item * DECRYPTION_KEY).collect();
+    fn part_1(items: &Self::Input<'_>) -> i64 {
+        let mixed = mix(items, 1);
+        let start_idx = mixed.iter().position(|&item| item == 0).unwrap();
 
-    let mixed = mix(&items, 10);
-    let start_idx = mixed.iter().position(|&item| item == 0).unwrap();
+        let mut sum = 0;
+        for pos in POSITIONS {
+            sum += mixed[(pos + start_idx) % mixed.len()];
+        }
+        sum
+    }
+
+    fn part_2(items: &Self::Input<'_>) -> i64 {
+        const DECRYPTION_KEY: i64 = 811_589_153;
+        let items: Vec<i64> = items.iter().map(|&item| item * DECRYPTION_KEY).collect();
 
-    let mut sum = 0;
-    for pos in POSITIONS {
-        sum += mixed[(pos + start_idx) % mixed.len()];
+        let mixed = mix(&items, 10);
+        let start_idx = mixed.iter().position(|&item| item == 0).unwrap();
+
+        let mut sum = 0;
+        for pos in POSITIONS {
+            sum += mixed[(pos + start_idx) % mixed.len()];
+        }
+        sum
     }
-    sum
 }
 
 const POSITIONS: [usize; 3] = [1000, 2000, 3000];
@@ -52,7 +66,7 @@ fn mix(items: &[i64], count: u32) -> Vec<i64> {
         .collect()
 }
 
-super::day_test! {demo_1 == 3}
-super::day_test! {demo_2 == 1623178306}
-super::day_test! {part_1 == 7004}
-super::day_test! {part_2 == 17200008919529}
+super::day_test! {demo_1}
+super::day_test! {demo_2}
+super::day_test! {part_1}
+super::day_test! {part_2}
@@ -0,0 +1,218 @@
+use crate::parse::{ParseError, Parser};
+use crate::Solution;
+use ahash::{HashMap, HashMapExt};
+
+type MonkeyId = u16;
+type Num = i64;
+
+#[derive(Debug, Copy, Clone)]
+enum Op {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+#[derive(Debug, Copy, Clone)]
+enum Job {
+    Num(Num),
+    Op(Op, MonkeyId, MonkeyId),
+}
+
+#[derive(Debug)]
+pub struct Monkeys {
+    jobs: Vec<Job>,
+    root: MonkeyId,
+    humn: MonkeyId,
+}
+
+impl Monkeys {
+    fn eval(&self, id: MonkeyId) -> Num {
+        match self.jobs[usize::from(id)] {
+            Job::Num(n) => n,
+            Job::Op(op, lhs, rhs) => {
+                let lhs = self.eval(lhs);
+                let rhs = self.eval(rhs);
+                match op {
+                    Op::Add => lhs + rhs,
+                    Op::Sub => lhs - rhs,
+                    Op::Mul => lhs * rhs,
+                    Op::Div => lhs / rhs,
+                }
+            }
+        }
+    }
+
+    // Evaluate with `humn` as an unknown, everything else is folded into a linear expression of it
+    fn eval_linear(&self, id: MonkeyId) -> Linear {
+        if id == self.humn {
+            return Linear::UNKNOWN;
+        }
+        match self.jobs[usize::from(id)] {
+            Job::Num(n) => Linear::constant(n.into()),
+            Job::Op(op, lhs, rhs) => {
+                let lhs = self.eval_linear(lhs);
+                let rhs = self.eval_linear(rhs);
+                match op {
+                    Op::Add => lhs.add(rhs),
+                    Op::Sub => lhs.sub(rhs),
+                    Op::Mul => lhs.mul(rhs),
+                    Op::Div => lhs.div(rhs),
+                }
+            }
+        }
+    }
+}
+
+/// `(coeff * humn + constant) / denom`
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+struct Linear {
+    coeff: i128,
+    constant: i128,
+    denom: i128,
+}
+
+impl Linear {
+    const UNKNOWN: Self = Self {
+        coeff: 1,
+        constant: 0,
+        denom: 1,
+    };
+
+    const fn constant(n: i128) -> Self {
+        Self {
+            coeff: 0,
+            constant: n,
+            denom: 1,
+        }
+    }
+
+    fn new(coeff: i128, constant: i128, denom: i128) -> Self {
+        assert_ne!(denom, 0, "division by zero");
+        let sign = denom.signum();
+        let divisor = gcd(gcd(coeff, constant), denom);
+        Self {
+            coeff: sign * coeff / divisor,
+            constant: sign * constant / divisor,
+            denom: sign * denom / divisor,
+        }
+    }
+
+    fn add(self, rhs: Self) -> Self {
+        Self::new(
+            self.coeff * rhs.denom + rhs.coeff * self.denom,
+            self.constant * rhs.denom + rhs.constant * self.denom,
+            self.denom * rhs.denom,
+        )
+    }
+
+    fn sub(self, rhs: Self) -> Self {
+        self.add(Self::new(-rhs.coeff, -rhs.constant, rhs.denom))
+    }
+
+    fn mul(self, rhs: Self) -> Self {
+        let (linear, factor) = match (self.coeff, rhs.coeff) {
+            (_, 0) => (self, rhs),
+            (0, _) => (rhs, self),
+            _ => panic!("humn appears on both sides of a multiplication"),
+        };
+        Self::new(
+            linear.coeff * factor.constant,
+            linear.constant * factor.constant,
+            linear.denom * factor.denom,
+        )
+    }
+
+    fn div(self, rhs: Self) -> Self {
+        assert_eq!(rhs.coeff, 0, "division by an expression containing humn");
+        Self::new(
+            self.coeff * rhs.denom,
+            self.constant * rhs.denom,
+            self.denom * rhs.constant,
+        )
+    }
+
+    // Solve `self == 0` for humn
+    fn solve_zero(self) -> i128 {
+        assert_ne!(self.coeff, 0, "humn does not affect the result");
+        assert_eq!(
+            self.constant % self.coeff,
+            0,
+            "humn has no integer solution"
+        );
+        -self.constant / self.coeff
+    }
+}
+
+fn gcd(mut a: i128, mut b: i128) -> i128 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a.abs()
+}
+
+pub struct Day;
+
+impl Solution for Day {
+    type Input<'a> = Monkeys;
+    type Answer1 = Num;
+    type Answer2 = i128;
+
+    fn parse(s: &str) -> Result<Self::Input<'_>, ParseError> {
+        let p = Parser::new(s);
+        let mut ids = HashMap::<&str, MonkeyId>::with_capacity(4096);
+        let mut id_of = |name| {
+            let next_id = MonkeyId::try_from(ids.len()).unwrap();
+            *ids.entry(name).or_insert(next_id)
+        };
+
+        let mut jobs = Vec::with_capacity(4096);
+        for line in s.lines() {
+            let (name, job) = p.split_once(line, ": ")?;
+            let id = usize::from(id_of(name));
+
+            let job = match job.split_once(' ') {
+                None => Job::Num(p.number(job)?),
+                Some((lhs, rest)) => {
+                    let (op, rhs) = p.split_once(rest, " ")?;
+                    let op = match op {
+                        "+" => Op::Add,
+                        "-" => Op::Sub,
+                        "*" => Op::Mul,
+                        "/" => Op::Div,
+                        _ => return Err(p.error(op, "an operation, \"+\", \"-\", \"*\" or \"/\"")),
+                    };
+                    Job::Op(op, id_of(lhs), id_of(rhs))
+                }
+            };
+            if jobs.len() <= id {
+                jobs.resize(id + 1, Job::Num(0));
+            }
+            jobs[id] = job;
+        }
+        let root = id_of("root");
+        let humn = id_of("humn");
+        if let Some((name, _)) = ids.iter().find(|(_, &id)| usize::from(id) >= jobs.len()) {
+            return Err(p.error_after(s, format!("a job for monkey {name:?}")));
+        }
+
+        Ok(Monkeys { jobs, root, humn })
+    }
+
+    fn part_1(monkeys: &Self::Input<'_>) -> Num {
+        monkeys.eval(monkeys.root)
+    }
+
+    fn part_2(monkeys: &Self::Input<'_>) -> i128 {
+        let Job::Op(_, lhs, rhs) = monkeys.jobs[usize::from(monkeys.root)] else {
+            panic!("root should compare two monkeys")
+        };
+        let lhs = monkeys.eval_linear(lhs);
+        let rhs = monkeys.eval_linear(rhs);
+
+        lhs.sub(rhs).solve_zero()
+    }
+}
+
+super::day_test! {demo_1}
+super::day_test! {demo_2}
@@ -0,0 +1,313 @@
+use crate::geom::{Dir4, Point2, Point3};
+use crate::grid::NlGrid;
+use crate::parse::{ParseError, Parser};
+use crate::Solution;
+
+type Pos = Point2<i32>;
+type Vec3 = Point3<i32>;
+
+// The value of facing each direction in the password
+fn facing_value(facing: Dir4) -> i32 {
+    match facing {
+        Dir4::Right => 0,
+        Dir4::Down => 1,
+        Dir4::Left => 2,
+        Dir4::Up => 3,
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+enum Step {
+    Forward(u32),
+    Left,
+    Right,
+}
+
+#[derive(Debug)]
+pub struct Notes<'a> {
+    // Rows are different lengths, past the end of one is off the map, like the spaces before it
+    map: NlGrid<'a>,
+    path: Vec<Step>,
+}
+
+pub struct Day;
+
+impl Solution for Day {
+    type Input<'a> = Notes<'a>;
+    type Answer1 = i32;
+    type Answer2 = i32;
+
+    fn parse(s: &str) -> Result<Self::Input<'_>, ParseError> {
+        let p = Parser::new(s);
+        let (map, path) = p.split_once(s, "\n\n")?;
+        let map = NlGrid::new(map);
+
+        let path = path.trim_end();
+        let mut steps = Vec::with_capacity(4096);
+        let mut n = None;
+        for (i, b) in path.bytes().enumerate() {
+            match b {
+                b'L' | b'R' => {
+                    if let Some(n) = n.take() {
+                        steps.push(Step::Forward(n));
+                    }
+                    steps.push(if b == b'L' { Step::Left } else { Step::Right });
+                }
+                b'0'..=b'9' => {
+                    let current_n = n.get_or_insert(0);
+                    *current_n = *current_n * 10 + u32::from(b - b'0');
+                }
+                _ => return Err(p.error(&path[i..], "a number, \"L\" or \"R\"")),
+            }
+        }
+        if let Some(n) = n {
+            steps.push(Step::Forward(n));
+        }
+
+        Ok(Notes { map, path: steps })
+    }
+
+    fn part_1(notes: &Self::Input<'_>) -> i32 {
+        walk(notes, |mut pos, facing| {
+            // Walk backwards until we hit the other edge
+            let delta = facing.offset();
+            while on_map(tile(&notes.map, pos - delta)) {
+                pos -= delta;
+            }
+            (pos, facing)
+        })
+    }
+
+    fn part_2(notes: &Self::Input<'_>) -> i32 {
+        let cube = Cube::fold(&notes.map);
+        walk(notes, |pos, facing| cube.wrap(pos, facing))
+    }
+}
+
+// The tile at `pos`, `None` past the ends of the rows
+fn tile(map: &NlGrid, pos: Pos) -> Option<u8> {
+    map.get(pos.x, pos.y)
+}
+
+fn on_map(tile: Option<u8>) -> bool {
+    matches!(tile, Some(b'.' | b'#'))
+}
+
+// Follow the path, calling `wrap` to find where we end up when walking off the edge of the map
+fn walk<F>(notes: &Notes, wrap: F) -> i32
+where
+    F: Fn(Pos, Dir4) -> (Pos, Dir4),
+{
+    let map = &notes.map;
+    let start_x = (0..)
+        .find(|&x| tile(map, Pos::new(x, 0)) == Some(b'.'))
+        .unwrap();
+
+    let mut pos = Pos::new(start_x, 0);
+    let mut facing = Dir4::Right;
+    for &step in &notes.path {
+        match step {
+            Step::Left => facing = facing.turn_left(),
+            Step::Right => facing = facing.turn_right(),
+            Step::Forward(n) => {
+                for _ in 0..n {
+                    let mut next = (pos + facing.offset(), facing);
+                    if !on_map(tile(map, next.0)) {
+                        next = wrap(pos, facing);
+                    }
+                    if tile(map, next.0) == Some(b'#') {
+                        break;
+                    }
+                    (pos, facing) = next;
+                }
+            }
+        }
+    }
+
+    1000 * (pos.y + 1) + 4 * (pos.x + 1) + facing_value(facing)
+}
+
+#[derive(Debug, Copy, Clone)]
+struct Face {
+    // Top left corner of the face on the map
+    origin: Pos,
+    // Outward normal of the face on the cube
+    normal: Vec3,
+    // Directions on the cube which correspond to right and down on the map
+    right: Vec3,
+    down: Vec3,
+}
+
+impl Face {
+    fn direction(&self, facing: Dir4) -> Vec3 {
+        match facing {
+            Dir4::Right => self.right,
+            Dir4::Down => self.down,
+            Dir4::Left => -self.right,
+            Dir4::Up => -self.down,
+        }
+    }
+
+    // The face reached by folding over the edge in the direction of `facing`
+    fn fold_over(&self, facing: Dir4, origin: Pos) -> Self {
+        // After rolling over the edge, the direction we were going now points into the cube
+        let n = self.normal;
+        let (right, down) = match facing {
+            Dir4::Right => (-n, self.down),
+            Dir4::Left => (n, self.down),
+            Dir4::Down => (self.right, -n),
+            Dir4::Up => (self.right, n),
+        };
+        Self {
+            origin,
+            normal: self.direction(facing),
+            right,
+            down,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Cube {
+    size: i32,
+    faces: Vec<Face>,
+}
+
+impl Cube {
+    fn fold(map: &NlGrid) -> Self {
+        let width = map.width() as i32;
+        let height = map.height() as i32;
+        let tiles = (0..height)
+            .flat_map(|y| (0..width).map(move |x| Pos::new(x, y)))
+            .filter(|&pos| on_map(tile(map, pos)))
+            .count();
+        let size = (tiles / 6).isqrt();
+        assert_eq!(size * size * 6, tiles, "map should cover 6 square faces");
+        let size = size as i32;
+
+        let first_x = (0..width)
+            .find(|&x| on_map(tile(map, Pos::new(x, 0))))
+            .unwrap();
+        let mut faces = Vec::with_capacity(6);
+        faces.push(Face {
+            origin: Pos::new(first_x, 0),
+            normal: Vec3::new(0, 0, 1),
+            right: Vec3::new(1, 0, 0),
+            down: Vec3::new(0, 1, 0),
+        });
+
+        let mut i = 0;
+        while i < faces.len() {
+            let face = faces[i];
+            for facing in Dir4::ALL {
+                let origin = face.origin + facing.offset() * size;
+                if !on_map(tile(map, origin)) || faces.iter().any(|f| f.origin == origin) {
+                    continue;
+                }
+                faces.push(face.fold_over(facing, origin));
+            }
+            i += 1;
+        }
+        assert_eq!(faces.len(), 6, "map should fold into a cube");
+
+        Self { size, faces }
+    }
+
+    fn face_at(&self, pos: Pos) -> &Face {
+        self.faces
+            .iter()
+            .find(|f| {
+                (f.origin.x..f.origin.x + self.size).contains(&pos.x)
+                    && (f.origin.y..f.origin.y + self.size).contains(&pos.y)
+            })
+            .unwrap()
+    }
+
+    fn wrap(&self, pos: Pos, facing: Dir4) -> (Pos, Dir4) {
+        let face = self.face_at(pos);
+        let travel = face.direction(facing);
+        let target = self.faces.iter().find(|f| f.normal == travel).unwrap();
+        // Once over the edge, we're heading away from the face we left
+        let new_facing = Dir4::ALL
+            .into_iter()
+            .find(|&f| target.direction(f) == -face.normal)
+            .unwrap();
+
+        // Cube coordinates, doubled so the center of every tile is an integer:
+        // the cube spans -size..=size on each axis
+        let max_offset = self.size - 1;
+        let local = pos - face.origin;
+        let cube_pos = face.normal * self.size
+            + face.right * (2 * local.x - max_offset)
+            + face.down * (2 * local.y - max_offset);
+        // Step half a tile over the edge, and half a tile down the next face
+        let cube_pos = cube_pos + travel - face.normal;
+
+        let new_local = Pos::new(
+            (dot(cube_pos, target.right) + max_offset) / 2,
+            (dot(cube_pos, target.down) + max_offset) / 2,
+        );
+        (target.origin + new_local, new_facing)
+    }
+}
+
+fn dot(lhs: Vec3, rhs: Vec3) -> i32 {
+    lhs.x * rhs.x + lhs.y * rhs.y + lhs.z * rhs.z
+}
+
+super::day_test! {demo_1}
+super::day_test! {demo_2}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Walking off an edge and turning around should always lead back to where we started
+    fn assert_wrap_reversible(map: &str) {
+        let map = NlGrid::new(map);
+        let cube = Cube::fold(&map);
+        for face in &cube.faces {
+            for facing in Dir4::ALL {
+                for i in 0..cube.size {
+                    let edge = cube.size - 1;
+                    let local = match facing {
+                        Dir4::Right => Pos::new(edge, i),
+                        Dir4::Down => Pos::new(i, edge),
+                        Dir4::Left => Pos::new(0, i),
+                        Dir4::Up => Pos::new(i, 0),
+                    };
+                    let pos = face.origin + local;
+                    if on_map(tile(&map, pos + facing.offset())) {
+                        continue;
+                    }
+                    let (new_pos, new_facing) = cube.wrap(pos, facing);
+                    assert!(on_map(tile(&map, new_pos)));
+                    assert_eq!(
+                        cube.wrap(new_pos, new_facing.reverse()),
+                        (pos, facing.reverse())
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn demo_net_wraps() {
+        let input = crate::day_test!(@demo_input);
+        assert_wrap_reversible(input.split_once("\n\n").unwrap().0);
+    }
+
+    // The shape of the real inputs' nets, at a smaller size
+    #[test]
+    fn input_shaped_net_wraps() {
+        let net = [" ##", " # ", "## ", "#  "];
+        let mut map = String::new();
+        for row in net {
+            for _ in 0..4 {
+                map.extend(row.chars().map(|ch| if ch == '#' { "...." } else { "    " }));
+                map.push('\n');
+            }
+        }
+        assert_wrap_reversible(&map);
+    }
+}
@@ -1,166 +1,191 @@
+use crate::geom::{Dir8, Point2};
+use crate::parse::{ParseError, Parser};
+use crate::Solution;
 use ahash::{HashMap, HashMapExt, HashSet, HashSetExt};
 use std::collections::hash_map::Entry;
 
-type Pos = (i32, i32);
+type Pos = Point2<i32>;
 
-pub fn generator(s: &str) -> HashSet<Pos> {
-    let mut result = HashSet::with_capacity(1024);
-    for (y, line) in s.lines().enumerate() {
-        for (x, ch) in line.bytes().enumerate() {
-            if ch == b'#' {
-                result.insert((x as i32, 0 - y as i32));
+pub struct Day;
+
+impl Solution for Day {
+    type Input<'a> = HashSet<Pos>;
+    type Answer1 = i32;
+    type Answer2 = u32;
+
+    fn parse(s: &str) -> Result<Self::Input<'_>, ParseError> {
+        let p = Parser::new(s);
+        let mut result = HashSet::with_capacity(1024);
+        for (y, line) in s.lines().enumerate() {
+            for (x, ch) in line.bytes().enumerate() {
+                match ch {
+                    b'#' => {
+                        result.insert(Pos::new(x as i32, y as i32));
+                    }
+                    b'.' => {}
+                    _ => return Err(p.error(&line[x..], "an elf \"#\", or \".\"")),
+                }
             }
         }
+        Ok(result)
     }
-    result
-}
 
-const CHECK_POSITIONS: [[Pos; 3]; 4] = [
-    [(0, 1), (-1, 1), (1, 1)],
-    [(0, -1), (-1, -1), (1, -1)],
-    [(-1, 0), (-1, -1), (-1, 1)],
-    [(1, 0), (1, -1), (1, 1)],
-];
+    fn part_1(positions: &Self::Input<'_>) -> i32 {
+        let mut check_pos_start = 0;
+        let mut proposals = HashMap::with_capacity(positions.len());
+        let mut conflicts = HashSet::with_capacity(positions.len());
+        let mut positions = positions.clone();
 
-pub fn part_1(positions: &HashSet<Pos>) -> i32 {
-    let mut check_pos_start = 0;
-    let mut proposals = HashMap::with_capacity(positions.len());
-    let mut conflicts = HashSet::with_capacity(positions.len());
-    let mut positions = positions.clone();
-
-    for _ in 0..10 {
-        proposals.clear();
-        conflicts.clear();
-        for &(x, y) in &positions {
-            let mut has_neighbor = false;
-            let mut proposed_direction = None;
-            'outer: for direction in CHECK_POSITIONS[check_pos_start..]
-                .iter()
-                .chain(&CHECK_POSITIONS[..check_pos_start])
-            {
-                for &(dx, dy) in direction {
-                    if positions.contains(&(x + dx, y + dy)) {
-                        has_neighbor = true;
-                        if proposed_direction.is_some() {
-                            break 'outer;
-                        } else {
-                            continue 'outer;
+        for _ in 0..10 {
+            proposals.clear();
+            conflicts.clear();
+            for &pos in &positions {
+                let mut has_neighbor = false;
+                let mut proposed_direction = None;
+                'outer: for direction in CHECK_POSITIONS[check_pos_start..]
+                    .iter()
+                    .chain(&CHECK_POSITIONS[..check_pos_start])
+                {
+                    for &dir in direction {
+                        if positions.contains(&(pos + dir.offset())) {
+                            has_neighbor = true;
+                            if proposed_direction.is_some() {
+                                break 'outer;
+                            } else {
+                                continue 'outer;
+                            }
                         }
                     }
-                }
-                if proposed_direction.is_none() {
-                    proposed_direction = Some(direction[0]);
-                    if has_neighbor {
-                        break;
+                    if proposed_direction.is_none() {
+                        proposed_direction = Some(direction[0]);
+                        if has_neighbor {
+                            break;
+                        }
                     }
                 }
-            }
 
-            if let Some((dx, dy)) = proposed_direction.filter(|_| has_neighbor) {
-                match proposals.entry((x + dx, y + dy)) {
-                    Entry::Occupied(_) => {
-                        conflicts.insert((x + dx, y + dy));
-                        proposals.insert((x, y), (x, y));
-                    }
-                    Entry::Vacant(e) => {
-                        e.insert((x, y));
+                if let Some(dir) = proposed_direction.filter(|_| has_neighbor) {
+                    let new_pos = pos + dir.offset();
+                    match proposals.entry(new_pos) {
+                        Entry::Occupied(_) => {
+                            conflicts.insert(new_pos);
+                            proposals.insert(pos, pos);
+                        }
+                        Entry::Vacant(e) => {
+                            e.insert(pos);
+                        }
                     }
+                } else {
+                    proposals.insert(pos, pos);
                 }
-            } else {
-                proposals.insert((x, y), (x, y));
             }
-        }
-        for conflict in &conflicts {
-            let orig = proposals.remove(conflict).unwrap();
-            proposals.insert(orig, orig);
-        }
+            for conflict in &conflicts {
+                let orig = proposals.remove(conflict).unwrap();
+                proposals.insert(orig, orig);
+            }
 
-        assert_eq!(proposals.len(), positions.len());
-        positions.clear();
-        positions.extend(proposals.keys());
-        check_pos_start += 1;
-        if check_pos_start == CHECK_POSITIONS.len() {
-            check_pos_start = 0;
+            assert_eq!(proposals.len(), positions.len());
+            positions.clear();
+            positions.extend(proposals.keys());
+            check_pos_start += 1;
+            if check_pos_start == CHECK_POSITIONS.len() {
+                check_pos_start = 0;
+            }
         }
+        let mut min_x = i32::MAX;
+        let mut min_y = i32::MAX;
+        let mut max_x = i32::MIN;
+        let mut max_y = i32::MIN;
+
+        positions.iter().for_each(|&Pos { x, y }| {
+            min_x = min_x.min(x);
+            min_y = min_y.min(y);
+            max_x = max_x.max(x + 1);
+            max_y = max_y.max(y + 1);
+        });
+
+        (max_x - min_x) * (max_y - min_y) - positions.len() as i32
     }
-    let mut min_x = i32::MAX;
-    let mut min_y = i32::MAX;
-    let mut max_x = i32::MIN;
-    let mut max_y = i32::MIN;
-
-    positions.iter().for_each(|&(x, y)| {
-        min_x = min_x.min(x);
-        min_y = min_y.min(y);
-        max_x = max_x.max(x + 1);
-        max_y = max_y.max(y + 1);
-    });
-
-    (max_x - min_x) * (max_y - min_y) - positions.len() as i32
-}
 
-pub fn part_2(positions: &HashSet<Pos>) -> u32 {
-    let mut check_pos_start = 0;
-    let mut proposals = HashMap::with_capacity(positions.len());
-    let mut conflicts = HashSet::with_capacity(positions.len());
-    let mut positions = positions.clone();
-
-    for i in 1.. {
-        proposals.clear();
-        conflicts.clear();
-        for &(x, y) in &positions {
-            let mut has_neighbor = false;
-            let mut proposed_direction = None;
-            'outer: for direction in CHECK_POSITIONS[check_pos_start..]
-                .iter()
-                .chain(&CHECK_POSITIONS[..check_pos_start])
-            {
-                for &(dx, dy) in direction {
-                    if positions.contains(&(x + dx, y + dy)) {
-                        has_neighbor = true;
-                        if proposed_direction.is_some() {
-                            break 'outer;
-                        } else {
-                            continue 'outer;
+    fn part_2(positions: &Self::Input<'_>) -> u32 {
+        let mut check_pos_start = 0;
+        let mut proposals = HashMap::with_capacity(positions.len());
+        let mut conflicts = HashSet::with_capacity(positions.len());
+        let mut positions = positions.clone();
+
+        for i in 1.. {
+            proposals.clear();
+            conflicts.clear();
+            for &pos in &positions {
+                let mut has_neighbor = false;
+                let mut proposed_direction = None;
+                'outer: for direction in CHECK_POSITIONS[check_pos_start..]
+                    .iter()
+                    .chain(&CHECK_POSITIONS[..check_pos_start])
+                {
+                    for &dir in direction {
+                        if positions.contains(&(pos + dir.offset())) {
+                            has_neighbor = true;
+                            if proposed_direction.is_some() {
+                                break 'outer;
+                            } else {
+                                continue 'outer;
+                            }
                         }
                     }
-                }
-                if proposed_direction.is_none() {
-                    proposed_direction = Some(direction[0]);
-                    if has_neighbor {
-                        break;
+                    if proposed_direction.is_none() {
+                        proposed_direction = Some(direction[0]);
+                        if has_neighbor {
+                            break;
+                        }
                     }
                 }
-            }
 
-            if let Some((dx, dy)) = proposed_direction.filter(|_| has_neighbor) {
-                match proposals.entry((x + dx, y + dy)) {
-                    Entry::Occupied(_) => {
-                        conflicts.insert((x + dx, y + dy));
-                        proposals.insert((x, y), (x, y));
-                    }
-                    Entry::Vacant(e) => {
-                        e.insert((x, y));
+                if let Some(dir) = proposed_direction.filter(|_| has_neighbor) {
+                    let new_pos = pos + dir.offset();
+                    match proposals.entry(new_pos) {
+                        Entry::Occupied(_) => {
+                            conflicts.insert(new_pos);
+                            proposals.insert(pos, pos);
+                        }
+                        Entry::Vacant(e) => {
+                            e.insert(pos);
+                        }
                     }
+                } else {
+                    proposals.insert(pos, pos);
                 }
-            } else {
-                proposals.insert((x, y), (x, y));
             }
-        }
-        for conflict in &conflicts {
-            let orig = proposals.remove(conflict).unwrap();
-            proposals.insert(orig, orig);
-        }
+            for conflict in &conflicts {
+                let orig = proposals.remove(conflict).unwrap();
+                proposals.insert(orig, orig);
+            }
 
-        if proposals.iter().all(|(&to, &from)| to == from) {
-            return i;
-        }
-        positions.clear();
-        positions.extend(proposals.keys());
-        check_pos_start += 1;
-        if check_pos_start == CHECK_POSITIONS.len() {
-            check_pos_start = 0;
+            if proposals.iter().all(|(&to, &from)| to == from) {
+                return i;
+            }
+            positions.clear();
+            positions.extend(proposals.keys());
+            check_pos_start += 1;
+            if check_pos_start == CHECK_POSITIONS.len() {
+                check_pos_start = 0;
+            }
         }
-    }
 
-    panic!("Expected to stop")
+        panic!("Expected to stop")
+    }
 }
+
+// North, south, west, then east, each with the diagonals next to it
+const CHECK_POSITIONS: [[Dir8; 3]; 4] = [
+    [Dir8::Up, Dir8::UpLeft, Dir8::UpRight],
+    [Dir8::Down, Dir8::DownLeft, Dir8::DownRight],
+    [Dir8::Left, Dir8::DownLeft, Dir8::UpLeft],
+    [Dir8::Right, Dir8::DownRight, Dir8::UpRight],
+];
+
+
+super::day_test! {demo_1}
+super::day_test! {part_1}
+super::day_test! {demo_2}
+super::day_test! {part_2}
@@ -1,12 +1,17 @@
-use ahash::{HashMap, HashMapExt, HashSet, HashSetExt};
+use crate::geom::Point2;
+use crate::parse::{ParseError, Parser};
+use crate::{search, Solution};
+use ahash::{HashMap, HashMapExt};
 use bitvec::BitArr;
-use std::collections::BTreeMap;
 
 type N = u16;
-type Pos = [N; 2];
+type Pos = Point2<N>;
+
+// The most rows or columns a field can have
+const MAX_SIZE: usize = 128;
 
 #[derive(Debug, Copy, Clone, Default)]
-struct StormLine(BitArr!(for 128));
+struct StormLine(BitArr!(for MAX_SIZE));
 
 #[derive(Debug, Clone)]
 pub struct Field {
@@ -22,77 +27,96 @@ pub struct Field {
     right: HashMap<N, StormLine>,
 }
 
-pub fn generator(s: &str) -> Field {
-    let mut lines = s.lines();
+pub struct Day;
+
+impl Solution for Day {
+    type Input<'a> = Field;
+    type Answer1 = u16;
+    type Answer2 = u16;
+
+    fn parse(s: &str) -> Result<Self::Input<'_>, ParseError> {
+        let p = Parser::new(s);
+        let mut lines = s.lines();
+        // The part of a line between the walls on either side
+        let inside_walls = |line| {
+            p.strip_prefix(line, "#")?
+                .strip_suffix('#')
+                .ok_or_else(|| p.error_after(line, "\"#\""))
+        };
+        // Where the gap is in the top or bottom wall
+        let gap = |line| -> Result<N, ParseError> {
+            let inside = inside_walls(line)?;
+            match inside.find('.') {
+                Some(x) => Ok(x as N),
+                None => Err(p.error(inside, "a gap in the wall, \".\"")),
+            }
+        };
 
-    let width;
-    let start_x = {
-        let first_line = lines.next().unwrap();
-        width = first_line.len() - 2;
-        N::try_from(first_line.find('.').unwrap() - 1).unwrap()
-    };
-    let end_x = {
-        let last_line = lines.next_back().unwrap();
-        N::try_from(last_line.find('.').unwrap() - 1).unwrap()
-    };
-    let mut y = 0;
-    let mut up = HashMap::<N, StormLine>::new();
-    let mut down = HashMap::<N, StormLine>::new();
-    let mut left = HashMap::<N, StormLine>::new();
-    let mut right = HashMap::<N, StormLine>::new();
-    for line in lines {
-        let line = &line[1..line.len() - 1];
-        for (x, ch) in line.bytes().enumerate() {
-            let x = x as N;
-            match ch {
-                b'.' => continue,
-                b'^' => up.entry(x).or_default().0.set(y as usize, true),
-                b'v' => down.entry(x).or_default().0.set(y as usize, true),
-                b'<' => left.entry(y).or_default().0.set(x as usize, true),
-                b'>' => right.entry(y).or_default().0.set(x as usize, true),
-                _ => panic!("Unknown map symbol {ch}"),
+        let first_line = p.next(&mut lines, s, "the top wall")?;
+        let width = inside_walls(first_line)?.len();
+        if width > MAX_SIZE {
+            return Err(p.error(first_line, format!("at most {} columns", MAX_SIZE)));
+        }
+        let start_x = gap(first_line)?;
+        let end_x = gap(lines.next_back().ok_or_else(|| p.error_after(s, "the bottom wall"))?)?;
+        let mut y = 0;
+        let mut up = HashMap::<N, StormLine>::new();
+        let mut down = HashMap::<N, StormLine>::new();
+        let mut left = HashMap::<N, StormLine>::new();
+        let mut right = HashMap::<N, StormLine>::new();
+        for line in lines {
+            let line = inside_walls(line)?;
+            if line.len() != width {
+                return Err(p.error(line, format!("{width} columns between the walls")));
+            }
+            if usize::from(y) >= MAX_SIZE {
+                return Err(p.error(line, format!("at most {} rows", MAX_SIZE)));
+            }
+            for (x, ch) in line.bytes().enumerate() {
+                let x = x as N;
+                match ch {
+                    b'.' => continue,
+                    b'^' => up.entry(x).or_default().0.set(y as usize, true),
+                    b'v' => down.entry(x).or_default().0.set(y as usize, true),
+                    b'<' => left.entry(y).or_default().0.set(x as usize, true),
+                    b'>' => right.entry(y).or_default().0.set(x as usize, true),
+                    _ => return Err(p.error(&line[x as usize..], "a blizzard, or \".\"")),
+                }
             }
+            y += 1;
         }
-        y += 1;
-    }
 
-    Field {
-        width: width.try_into().unwrap(),
-        height: y,
-        start_x,
-        end_x,
-        up,
-        down,
-        left,
-        right,
+        Ok(Field {
+            width: width as N,
+            height: y,
+            start_x,
+            end_x,
+            up,
+            down,
+            left,
+            right,
+        })
     }
-}
 
-pub fn part_1(field: &Field) -> u16 {
-    let turns = a_star(field, 1, false);
-
-    turns
-}
-
-pub fn part_2(field: &Field) -> u16 {
-    let first_leg = a_star(field, 1, false);
-    let second_leg = a_star(field, first_leg + 1, true);
-    let third_leg = a_star(field, second_leg + 1, false);
-
-    third_leg
-}
+    fn part_1(field: &Self::Input<'_>) -> u16 {
+        crossing(field, 0, false)
+    }
 
-const fn heuristic(src: Pos, dst: Pos) -> N {
-    src[0].abs_diff(dst[0]) + src[1].abs_diff(dst[1])
+    fn part_2(field: &Self::Input<'_>) -> u16 {
+        let first_leg = crossing(field, 0, false);
+        let second_leg = crossing(field, first_leg, true);
+        crossing(field, second_leg, false)
+    }
 }
 
-fn a_star(field: &Field, mut start_turn: u16, reverse: bool) -> u16 {
-    let mut frontier = BTreeMap::<u16, Vec<(Pos, u16)>>::new();
-    let mut visited = HashSet::with_capacity(1024);
-
+/// The turn the valley is crossed, starting from one side at `start_turn`
+///
+/// Positions here are a row lower than in the [`Field`], so the gaps in the walls on either side
+/// are rows `0` and `height + 1`, where there are never any blizzards.
+fn crossing(field: &Field, start_turn: u16, reverse: bool) -> u16 {
     let (start, end) = {
-        let start = [field.start_x, 0];
-        let end = [field.end_x, field.height - 1];
+        let start = Pos::new(field.start_x, 0);
+        let end = Pos::new(field.end_x, field.height + 1);
         if reverse {
             (end, start)
         } else {
@@ -100,64 +124,39 @@ fn a_star(field: &Field, mut start_turn: u16, reverse: bool) -> u16 {
         }
     };
 
-    loop {
-        while field.is_taken(start_turn, start) {
-            start_turn += 1;
-        }
-        frontier.insert(0, vec![(start, start_turn)]);
-
-        while let Some((cost_est, mut items)) = frontier.pop_first() {
-            while let Some((current, current_turn)) = items.pop() {
-                if current == end {
-                    return current_turn + 1;
-                }
-
-                let mut best_estimate = u16::MAX;
-                for diff in OFFSETS {
-                    let Some(new_x) = current[0].checked_add_signed(diff[0]) else { continue };
-                    if new_x >= field.width {
-                        continue;
-                    }
-                    let Some(new_y) = current[1].checked_add_signed(diff[1]) else { continue };
-                    if new_y >= field.height {
-                        continue;
-                    }
-                    let new_pos = [new_x, new_y];
-                    let new_turn = current_turn + 1;
-
-                    if !visited.insert((new_pos, new_turn)) {
-                        continue;
-                    }
-                    if field.is_taken(new_turn, new_pos) {
-                        continue;
-                    }
-                    let new_estimate = new_turn + heuristic(new_pos, end);
-                    frontier
-                        .entry(new_estimate)
-                        .or_default()
-                        .push((new_pos, new_turn));
-                    best_estimate = best_estimate.min(new_estimate);
-                }
-
-                // If we find a better estimate in the middle of a list of items,
-                // add the list back, and start from there
-                if best_estimate < cost_est && !items.is_empty() {
-                    frontier
-                        .entry(cost_est)
-                        .or_default()
-                        .extend_from_slice(&items);
-                    break;
-                }
-            }
-        }
-        start_turn += 1;
-    }
+    let successors = |(current, turn): (Pos, u16)| {
+        let new_turn = turn + 1;
+        OFFSETS.into_iter().filter_map(move |diff| {
+            let new_pos = current.checked_add_signed(diff)?;
+            let in_valley = new_pos.x < field.width && (1..=field.height).contains(&new_pos.y);
+            let free = if in_valley {
+                !field.is_taken(new_turn, Pos::new(new_pos.x, new_pos.y - 1))
+            } else {
+                new_pos == start || new_pos == end
+            };
+            free.then_some(((new_pos, new_turn), 1))
+        })
+    };
+    let search = search::astar(
+        [(start, start_turn)],
+        successors,
+        |(pos, _)| pos.manhattan(end),
+        |(pos, _)| pos == end,
+    );
+    start_turn + search.goal_cost().expect("the valley can always be crossed")
 }
 
-const OFFSETS: [[i16; 2]; 5] = [[0, 0], [-1, 0], [1, 0], [0, -1], [0, 1]];
+// Waiting, then moving in each direction
+const OFFSETS: [Point2<i16>; 5] = [
+    Point2::new(0, 0),
+    Point2::new(-1, 0),
+    Point2::new(1, 0),
+    Point2::new(0, -1),
+    Point2::new(0, 1),
+];
 
 impl Field {
-    fn is_taken(&self, turn: u16, [x, y]: Pos) -> bool {
+    fn is_taken(&self, turn: u16, Pos { x, y }: Pos) -> bool {
         let vert_turn_offset = turn % self.height;
         let horiz_turn_offset = turn % self.width;
 
@@ -166,7 +165,7 @@ impl Field {
             if self
                 .up
                 .get(&x)
-                .map_or(false, |storms| storms.0[orig_y as usize])
+                .is_some_and(|storms| storms.0[orig_y as usize])
             {
                 return true;
             }
@@ -176,7 +175,7 @@ impl Field {
             if self
                 .down
                 .get(&x)
-                .map_or(false, |storms| storms.0[orig_y as usize])
+                .is_some_and(|storms| storms.0[orig_y as usize])
             {
                 return true;
             }
@@ -186,7 +185,7 @@ impl Field {
             if self
                 .left
                 .get(&y)
-                .map_or(false, |storms| storms.0[orig_x as usize])
+                .is_some_and(|storms| storms.0[orig_x as usize])
             {
                 return true;
             }
@@ -196,7 +195,7 @@ impl Field {
             if self
                 .right
                 .get(&y)
-                .map_or(false, |storms| storms.0[orig_x as usize])
+                .is_some_and(|storms| storms.0[orig_x as usize])
             {
                 return true;
             }
@@ -205,7 +204,7 @@ impl Field {
     }
 }
 
-super::day_test! {demo_1 == 18}
-super::day_test! {demo_2 == 54}
-super::day_test! {part_1 == 283}
-super::day_test! {part_2 == 883}
+super::day_test! {demo_1}
+super::day_test! {demo_2}
+super::day_test! {part_1}
+super::day_test! {part_2}
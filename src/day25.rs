@@ -1,43 +1,71 @@
-pub(crate) use super::unimplemented_part as part_2;
+use crate::parse::{ParseError, Parser};
+use crate::{NoAnswer, Solution};
 use std::fmt::Write;
 use std::str::FromStr;
 use std::{fmt, mem};
 
-pub fn generator(s: &str) -> Vec<&str> {
-    s.lines().collect()
-}
+pub struct Day;
+
+impl Solution for Day {
+    type Input<'a> = Vec<SnafuNum>;
+    type Answer1 = SnafuNum;
+    type Answer2 = NoAnswer;
 
-pub fn part_1(items: &[&str]) -> SnafuNum {
-    let mut total = SnafuNum(0);
-    for item in items {
-        let snafu: SnafuNum = item.parse().unwrap();
-        total.0 += snafu.0;
+    const HAS_PART_2: bool = false;
+
+    fn parse(s: &str) -> Result<Self::Input<'_>, ParseError> {
+        let p = Parser::new(s);
+        s.lines()
+            .map(|line| {
+                if let Some(i) = line.find(|ch| !matches!(ch, '2' | '1' | '0' | '-' | '=')) {
+                    return Err(p.error(&line[i..], "a SNAFU digit, one of 2, 1, 0, - or ="));
+                }
+                line.parse().map_err(|e| p.error(line, e))
+            })
+            .collect()
+    }
+
+    fn part_1(items: &Self::Input<'_>) -> SnafuNum {
+        let mut total = SnafuNum(0);
+        for item in items {
+            total.0 += item.0;
+        }
+        total
+    }
+
+    fn part_2(_: &Self::Input<'_>) -> NoAnswer {
+        unreachable!("day 25 only has one part")
     }
-    total
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct SnafuNum(u64);
 
+/// The error is what was expected instead
 impl FromStr for SnafuNum {
     type Err = &'static str;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Err("a SNAFU number");
+        }
         let mut res = 0i64;
         for b in s.bytes() {
-            res *= 5;
             let digit = match b {
                 b'2' => 2,
                 b'1' => 1,
                 b'0' => 0,
                 b'-' => -1,
                 b'=' => -2,
-                _ => return Err("invalid digit"),
+                _ => return Err("a SNAFU digit, one of 2, 1, 0, - or ="),
             };
-            res += digit;
+            res = res
+                .checked_mul(5)
+                .and_then(|res| res.checked_add(digit))
+                .ok_or("a SNAFU number which fits in 64 bits")?;
         }
         if res < 0 {
-            return Err("negative value");
+            return Err("a positive SNAFU number");
         }
 
         Ok(SnafuNum(res as u64))
@@ -84,8 +112,8 @@ impl fmt::Display for SnafuNum {
     }
 }
 
-super::day_test! {demo_1 == SnafuNum::from_str("2=-1=0").unwrap()}
-super::day_test! {part_1 == SnafuNum::from_str("2-==10===-12=2-1=-=0").unwrap()}
+super::day_test! {demo_1}
+super::day_test! {part_1}
 
 #[cfg(test)]
 mod tests {
@@ -117,6 +145,15 @@ mod tests {
         }
     }
 
+    #[test]
+    fn invalid_snafu() {
+        let err = Day::parse("1=\n2-3\n").unwrap_err();
+        assert_eq!(err.to_string(), "2:3: expected a SNAFU digit, one of 2, 1, 0, - or =");
+        let err = Day::parse("1\n-1\n").unwrap_err();
+        assert_eq!(err.to_string(), "2:1: expected a positive SNAFU number");
+        assert!("2222222222222222222222222222".parse::<SnafuNum>().is_err());
+    }
+
     #[test]
     fn decimal_to_snafu() {
         for &(dec, snaf) in EXAMPLES {
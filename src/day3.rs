@@ -1,3 +1,6 @@
+use crate::parse::{ParseError, Parser};
+use crate::Solution;
+
 #[derive(Debug, Copy, Clone)]
 pub struct Rucksack<'a>(&'a [u8]);
 
@@ -19,7 +22,7 @@ fn set(items: &[u8]) -> u64 {
         let shift = match item {
             b'a'..=b'z' => item - b'a',
             _ => {
-                debug_assert!((b'A'..=b'Z').contains(&item));
+                debug_assert!(item.is_ascii_uppercase());
                 item - b'A' + 26
             }
         };
@@ -36,33 +39,46 @@ fn priority_from_set(set: u64) -> u32 {
     bit_set + 1
 }
 
-pub fn generator(s: &str) -> Vec<Rucksack> {
-    let mut result = Vec::with_capacity(1024);
-    s.lines()
-        .for_each(|line| result.push(Rucksack(line.as_bytes())));
-    result
-}
+pub struct Day;
+
+impl Solution for Day {
+    type Input<'a> = Vec<Rucksack<'a>>;
+    type Answer1 = u32;
+    type Answer2 = u32;
 
-pub fn part_1(bags: &[Rucksack]) -> u32 {
-    let mut total_score = 0;
-    for (left, right) in bags.iter().copied().map(Rucksack::split_set) {
-        let intersection = left & right;
+    fn parse(s: &str) -> Result<Self::Input<'_>, ParseError> {
+        let p = Parser::new(s);
+        let mut result = Vec::with_capacity(1024);
+        for line in s.lines() {
+            if let Some(i) = line.bytes().position(|b| !b.is_ascii_alphabetic()) {
+                return Err(p.error(&line[i..], "an item, a-z or A-Z"));
+            }
+            result.push(Rucksack(line.as_bytes()));
+        }
+        Ok(result)
+    }
 
-        total_score += priority_from_set(intersection);
+    fn part_1(bags: &Self::Input<'_>) -> u32 {
+        let mut total_score = 0;
+        for (left, right) in bags.iter().copied().map(Rucksack::split_set) {
+            let intersection = left & right;
+
+            total_score += priority_from_set(intersection);
+        }
+        total_score
     }
-    total_score
-}
 
-pub fn part_2(bags: &[Rucksack]) -> u32 {
-    let mut total_score = 0;
-    for chunk in bags.chunks_exact(3) {
-        let intersection = chunk.iter().fold(!0, |i, sack| i & sack.set());
-        total_score += priority_from_set(intersection);
+    fn part_2(bags: &Self::Input<'_>) -> u32 {
+        let mut total_score = 0;
+        for chunk in bags.chunks_exact(3) {
+            let intersection = chunk.iter().fold(!0, |i, sack| i & sack.set());
+            total_score += priority_from_set(intersection);
+        }
+        total_score
     }
-    total_score
 }
 
-super::day_test! {demo_1 == 157}
-super::day_test! {demo_2 == 70}
-super::day_test! {part_1 == 7908}
-super::day_test! {part_2 == 2838}
+super::day_test! {demo_1}
+super::day_test! {demo_2}
+super::day_test! {part_1}
+super::day_test! {part_2}
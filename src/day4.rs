@@ -1,36 +1,47 @@
-use std::str::FromStr;
+use crate::parse::{ParseError, Parser};
+use crate::Solution;
 
 type Range = (u32, u32);
 type Pair = (Range, Range);
 
-pub fn generator(s: &str) -> Vec<Pair> {
-    let mut result = Vec::with_capacity(1024);
-    s.lines().for_each(|line| {
-        let mut items = line.split([',', '-']).map(|s| u32::from_str(s).unwrap());
-
-        result.push((
-            (items.next().unwrap(), items.next().unwrap()),
-            (items.next().unwrap(), items.next().unwrap()),
-        ))
-    });
-    result
-}
+pub struct Day;
 
-pub fn part_1(assignments: &[Pair]) -> usize {
-    assignments
-        .iter()
-        .filter(|(l, r)| (l.0 <= r.0 && l.1 >= r.1) || (r.0 <= l.0 && r.1 >= l.1))
-        .count()
-}
+impl Solution for Day {
+    type Input<'a> = Vec<Pair>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(s: &str) -> Result<Self::Input<'_>, ParseError> {
+        let p = Parser::new(s);
+        let range = |s| -> Result<Range, ParseError> {
+            let (start, end) = p.split_once(s, "-")?;
+            Ok((p.number(start)?, p.number(end)?))
+        };
+
+        let mut result = Vec::with_capacity(1024);
+        for line in s.lines() {
+            let (first, second) = p.split_once(line, ",")?;
+            result.push((range(first)?, range(second)?));
+        }
+        Ok(result)
+    }
+
+    fn part_1(assignments: &Self::Input<'_>) -> usize {
+        assignments
+            .iter()
+            .filter(|(l, r)| (l.0 <= r.0 && l.1 >= r.1) || (r.0 <= l.0 && r.1 >= l.1))
+            .count()
+    }
 
-pub fn part_2(assignments: &[Pair]) -> usize {
-    assignments
-        .iter()
-        .filter(|(l, r)| l.0 <= r.1 && l.1 >= r.0)
-        .count()
+    fn part_2(assignments: &Self::Input<'_>) -> usize {
+        assignments
+            .iter()
+            .filter(|(l, r)| l.0 <= r.1 && l.1 >= r.0)
+            .count()
+    }
 }
 
-super::day_test! {demo_1 == 2}
-super::day_test! {demo_2 == 4}
-super::day_test! {part_1 == 441}
-super::day_test! {part_2 == 861}
+super::day_test! {demo_1}
+super::day_test! {demo_2}
+super::day_test! {part_1}
+super::day_test! {part_2}
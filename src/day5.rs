@@ -1,3 +1,5 @@
+use crate::parse::{ParseError, Parser};
+use crate::Solution;
 use std::fmt;
 use std::fmt::Write;
 
@@ -51,40 +53,49 @@ pub struct Input {
     operations: Vec<Operation>,
 }
 
-pub fn generator(s: &str) -> Input {
-    let (stacks, operations) = s.split_once("\n\n").unwrap();
-    let stacks = parse_stacks(stacks);
-    let operations = parse_operations(operations);
+pub struct Day;
 
-    Input { stacks, operations }
-}
+impl Solution for Day {
+    type Input<'a> = Input;
+    type Answer1 = String;
+    type Answer2 = String;
 
-pub fn part_1(input: &Input) -> String {
-    let mut stacks = input.stacks.clone();
-    for op in &input.operations {
-        stacks.apply::<true>(op);
-    }
+    fn parse(s: &str) -> Result<Self::Input<'_>, ParseError> {
+        let p = Parser::new(s);
+        let (stacks, operations) = p.split_once(s, "\n\n")?;
+        let stacks = parse_stacks(stacks);
+        let operations = parse_operations(&p, operations)?;
 
-    let mut word = String::with_capacity(stacks.items.len());
-    for column in &stacks.items {
-        word.push(column.last().copied().unwrap() as char)
+        Ok(Input { stacks, operations })
     }
 
-    word
-}
+    fn part_1(input: &Self::Input<'_>) -> String {
+        let mut stacks = input.stacks.clone();
+        for op in &input.operations {
+            stacks.apply::<true>(op);
+        }
 
-pub fn part_2(input: &Input) -> String {
-    let mut stacks = input.stacks.clone();
-    for op in &input.operations {
-        stacks.apply::<false>(op);
-    }
+        let mut word = String::with_capacity(stacks.items.len());
+        for column in &stacks.items {
+            word.push(column.last().copied().unwrap() as char)
+        }
 
-    let mut word = String::with_capacity(stacks.items.len());
-    for column in &stacks.items {
-        word.push(column.last().copied().unwrap() as char)
+        word
     }
 
-    word
+    fn part_2(input: &Self::Input<'_>) -> String {
+        let mut stacks = input.stacks.clone();
+        for op in &input.operations {
+            stacks.apply::<false>(op);
+        }
+
+        let mut word = String::with_capacity(stacks.items.len());
+        for column in &stacks.items {
+            word.push(column.last().copied().unwrap() as char)
+        }
+
+        word
+    }
 }
 
 fn parse_stacks(s: &str) -> CrateStacks {
@@ -112,31 +123,21 @@ fn parse_stacks(s: &str) -> CrateStacks {
     CrateStacks { items: columns }
 }
 
-fn parse_operations(s: &str) -> Vec<Operation> {
+fn parse_operations(p: &Parser, s: &str) -> Result<Vec<Operation>, ParseError> {
     let mut result = Vec::with_capacity(1024);
     for line in s.lines() {
-        let mut words = line.split(' ');
-
-        let r#move = words.next().unwrap_or_default();
-        let count = words.next().unwrap_or_default();
-        let from = words.next().unwrap_or_default();
-        let source = words.next().unwrap_or_default();
-        let to = words.next().unwrap_or_default();
-        let dest = words.next().unwrap_or_default();
-
-        debug_assert!(words.next().is_none());
-        debug_assert_eq!(r#move, "move");
-        debug_assert_eq!(from, "from");
-        debug_assert_eq!(to, "to");
+        let line = p.strip_prefix(line, "move ")?;
+        let (count, line) = p.split_once(line, " from ")?;
+        let (source, dest) = p.split_once(line, " to ")?;
 
         result.push(Operation {
-            count: count.parse().unwrap(),
-            source: source.parse().unwrap(),
-            dest: dest.parse().unwrap(),
+            count: p.number(count)?,
+            source: p.number(source)?,
+            dest: p.number(dest)?,
         });
     }
 
-    result
+    Ok(result)
 }
 
 impl fmt::Debug for CrateStacks {
@@ -155,7 +156,7 @@ impl fmt::Debug for CrateStacks {
     }
 }
 
-super::day_test! {demo_1 == "CMZ"}
-super::day_test! {demo_2 == "MCD"}
-super::day_test! {part_1 == "VRWBSFZWM"}
-super::day_test! {part_2 == "RBTWJWMCF"}
+super::day_test! {demo_1}
+super::day_test! {demo_2}
+super::day_test! {part_1}
+super::day_test! {part_2}
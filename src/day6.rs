@@ -1,13 +1,24 @@
-pub fn generator(s: &str) -> &str {
-    s.trim_end()
-}
+use crate::parse::ParseError;
+use crate::Solution;
 
-pub fn part_1(s: &str) -> usize {
-    find_non_duplicate_window(s, 4)
-}
+pub struct Day;
+
+impl Solution for Day {
+    type Input<'a> = &'a str;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(s: &str) -> Result<Self::Input<'_>, ParseError> {
+        Ok(s.trim_end())
+    }
+
+    fn part_1(s: &Self::Input<'_>) -> usize {
+        find_non_duplicate_window(s, 4)
+    }
 
-pub fn part_2(s: &str) -> usize {
-    find_non_duplicate_window(s, 14)
+    fn part_2(s: &Self::Input<'_>) -> usize {
+        find_non_duplicate_window(s, 14)
+    }
 }
 
 // Find the first `window_size` consecutive bytes containing no duplicates
@@ -33,7 +44,7 @@ fn find_non_duplicate_window(s: &str, window_size: usize) -> usize {
     }
 }
 
-super::day_test! {demo_1 == 7}
-super::day_test! {demo_2 == 19}
-super::day_test! {part_1 == 1287}
-super::day_test! {part_2 == 3716}
+super::day_test! {demo_1}
+super::day_test! {demo_2}
+super::day_test! {part_1}
+super::day_test! {part_2}
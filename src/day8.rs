@@ -1,147 +1,136 @@
-use bitvec::bitbox;
+use crate::grid::{BitGrid, Grid};
+use crate::parse::{ParseError, Parser};
+use crate::Solution;
 use std::cmp;
 
-#[derive(Debug)]
-pub struct Grid<'a> {
-    width: usize,
-    bytes: &'a [u8],
-}
+pub struct Day;
 
-impl Grid<'_> {
-    fn width(&self) -> usize {
-        self.width
-    }
+impl Solution for Day {
+    type Input<'a> = Grid<u8>;
+    type Answer1 = usize;
+    type Answer2 = u32;
 
-    fn height(&self) -> usize {
-        self.bytes.len() / self.width
-    }
-
-    fn get(&self, x: usize, y: usize) -> u8 {
-        self.bytes[x + y * (self.width + 1)]
-    }
-}
-
-pub fn generator(s: &str) -> Grid {
-    let width = s.lines().next().unwrap().len();
-    Grid {
-        width,
-        bytes: s.trim_end().as_bytes(),
+    fn parse(s: &str) -> Result<Self::Input<'_>, ParseError> {
+        let p = Parser::new(s);
+        let width = p.next(&mut s.lines(), s, "a row of trees")?.len();
+        if let Some(line) = s.lines().find(|line| line.len() != width) {
+            return Err(p.error(line, format!("a row of {width} trees")));
+        }
+        Ok(Grid::from_vec(width, s.lines().flat_map(str::bytes).collect()))
     }
-}
 
-pub fn part_1(grid: &Grid) -> usize {
-    let width = grid.width();
-    let height = grid.height();
+    fn part_1(grid: &Self::Input<'_>) -> usize {
+        let width = grid.width();
+        let height = grid.height();
 
-    let pos = |x: usize, y: usize| y * width + x;
-    let mut visible = bitbox![0; width * height];
-    let mut max_seen = vec![0u8; cmp::max(width, height)];
+        let mut visible = BitGrid::new(width, height);
+        let mut max_seen = vec![0u8; cmp::max(width, height)];
 
-    // top
-    for y in 0..height {
-        for x in 0..width {
-            let value = grid.get(x, y);
-            if value > max_seen[x] {
-                visible.set(pos(x, y), true);
-                max_seen[x] = value;
+        // top
+        for y in 0..height {
+            for (x, max_seen) in max_seen[..width].iter_mut().enumerate() {
+                let value = grid[(x, y)];
+                if value > *max_seen {
+                    visible.set(x, y, true);
+                    *max_seen = value;
+                }
             }
         }
-    }
 
-    max_seen.fill(0);
-    // bottom
-    for y in (0..height).rev() {
-        for x in 0..width {
-            let value = grid.get(x, y);
-            if value > max_seen[x] {
-                visible.set(pos(x, y), true);
-                max_seen[x] = value;
+        max_seen.fill(0);
+        // bottom
+        for y in (0..height).rev() {
+            for (x, max_seen) in max_seen[..width].iter_mut().enumerate() {
+                let value = grid[(x, y)];
+                if value > *max_seen {
+                    visible.set(x, y, true);
+                    *max_seen = value;
+                }
             }
         }
-    }
 
-    max_seen.fill(0);
-    // left
-    for x in 0..width {
-        for y in 0..height {
-            let value = grid.get(x, y);
-            if value > max_seen[y] {
-                visible.set(pos(x, y), true);
-                max_seen[y] = value;
+        max_seen.fill(0);
+        // left
+        for x in 0..width {
+            for (y, max_seen) in max_seen[..height].iter_mut().enumerate() {
+                let value = grid[(x, y)];
+                if value > *max_seen {
+                    visible.set(x, y, true);
+                    *max_seen = value;
+                }
             }
         }
-    }
 
-    max_seen.fill(0);
-    // right
-    for x in (0..width).rev() {
-        for y in 0..height {
-            let value = grid.get(x, y);
-            if value > max_seen[y] {
-                visible.set(pos(x, y), true);
-                max_seen[y] = value;
+        max_seen.fill(0);
+        // right
+        for x in (0..width).rev() {
+            for (y, max_seen) in max_seen[..height].iter_mut().enumerate() {
+                let value = grid[(x, y)];
+                if value > *max_seen {
+                    visible.set(x, y, true);
+                    *max_seen = value;
+                }
             }
         }
-    }
 
-    visible.count_ones()
-}
+        visible.count_ones()
+    }
 
-pub fn part_2(grid: &Grid) -> u32 {
-    let width = grid.width();
-    let height = grid.height();
+    fn part_2(grid: &Self::Input<'_>) -> u32 {
+        let width = grid.width();
+        let height = grid.height();
 
-    let pos = |x: usize, y: usize| y * width + x;
-    let mut scores = vec![1u32; width * height];
+        let mut scores = Grid::filled(width, height, 1u32);
 
-    let mut seen_pos = vec![[0; 10]; cmp::max(width, height)];
+        let mut seen_pos = vec![[0; 10]; cmp::max(width, height)];
 
-    // top
-    for y in 0..height {
-        for x in 0..width {
-            let tree_height = grid.get(x, y) - b'0';
-            let distance = y - seen_pos[x][usize::from(tree_height)];
-            scores[pos(x, y)] *= distance as u32;
-            seen_pos[x][..=usize::from(tree_height)].fill(y);
+        // top
+        for y in 0..height {
+            for x in 0..width {
+                let tree_height = grid[(x, y)] - b'0';
+                let distance = y - seen_pos[x][usize::from(tree_height)];
+                scores[(x, y)] *= distance as u32;
+                seen_pos[x][..=usize::from(tree_height)].fill(y);
+            }
         }
-    }
 
-    seen_pos.fill([height - 1; 10]);
-    // bottom
-    for y in (0..height).rev() {
-        for x in 0..width {
-            let tree_height = grid.get(x, y) - b'0';
-            let distance = seen_pos[x][usize::from(tree_height)] - y;
-            scores[pos(x, y)] *= distance as u32;
-            seen_pos[x][..=usize::from(tree_height)].fill(y);
+        seen_pos.fill([height - 1; 10]);
+        // bottom
+        for y in (0..height).rev() {
+            for x in 0..width {
+                let tree_height = grid[(x, y)] - b'0';
+                let distance = seen_pos[x][usize::from(tree_height)] - y;
+                scores[(x, y)] *= distance as u32;
+                seen_pos[x][..=usize::from(tree_height)].fill(y);
+            }
         }
-    }
 
-    seen_pos.fill([0; 10]);
-    // left
-    for x in 0..width {
-        for y in 0..height {
-            let tree_height = grid.get(x, y) - b'0';
-            let distance = x - seen_pos[y][usize::from(tree_height)];
-            scores[pos(x, y)] *= distance as u32;
-            seen_pos[y][..=usize::from(tree_height)].fill(x);
+        seen_pos.fill([0; 10]);
+        // left
+        for x in 0..width {
+            for y in 0..height {
+                let tree_height = grid[(x, y)] - b'0';
+                let distance = x - seen_pos[y][usize::from(tree_height)];
+                scores[(x, y)] *= distance as u32;
+                seen_pos[y][..=usize::from(tree_height)].fill(x);
+            }
         }
-    }
-    seen_pos.fill([width - 1; 10]);
-    // right
-    for x in (0..width).rev() {
-        for y in 0..height {
-            let tree_height = grid.get(x, y) - b'0';
-            let distance = seen_pos[y][usize::from(tree_height)] - x;
-            scores[pos(x, y)] *= distance as u32;
-            seen_pos[y][..=usize::from(tree_height)].fill(x);
+        seen_pos.fill([width - 1; 10]);
+        // right
+        for x in (0..width).rev() {
+            for y in 0..height {
+                let tree_height = grid[(x, y)] - b'0';
+                let distance = seen_pos[y][usize::from(tree_height)] - x;
+                scores[(x, y)] *= distance as u32;
+                seen_pos[y][..=usize::from(tree_height)].fill(x);
+            }
         }
-    }
 
-    scores.iter().copied().max().unwrap()
+        scores.cells().iter().copied().max().unwrap()
+    }
 }
 
-super::day_test! {demo_1 == 21}
-super::day_test! {demo_2 == 8}
-super::day_test! {part_1 == 1792}
-super::day_test! {part_2 == 334880}
+super::day_test! {demo_1}
+super::day_test! {demo_2}
+super::day_test! {part_1}
+super::day_test! {part_2}
@@ -1,92 +1,72 @@
+use crate::geom::{Dir4, Point2};
+use crate::parse::{ParseError, Parser};
+use crate::Solution;
 use ahash::{HashSet, HashSetExt};
-use std::cmp;
 
-type Dist = i32;
-
-type Vect = (Dist, Dist);
-
-#[repr(u8)]
-#[derive(Debug, Copy, Clone)]
-enum Dir {
-    Up = b'U',
-    Down = b'D',
-    Left = b'L',
-    Right = b'R',
-}
-
-impl Dir {
-    fn to_vect(self) -> Vect {
-        match self {
-            Self::Up => (0, 1),
-            Self::Down => (0, -1),
-            Self::Left => (-1, 0),
-            Self::Right => (1, 0),
-        }
-    }
-}
+type Vect = Point2<i32>;
 
 #[derive(Debug)]
 pub struct Move {
-    dir: Dir,
+    dir: Dir4,
     count: u32,
 }
 
-pub fn generator(s: &str) -> Vec<Move> {
-    let mut result = Vec::with_capacity(2048);
-    for line in s.lines() {
-        let dir = line.as_bytes()[0];
-        // Direction is one byte, and skip the space
-        let count_str = &line[2..];
-        let count = count_str.parse().unwrap();
-
-        let dir = match dir {
-            b'U' => Dir::Up,
-            b'D' => Dir::Down,
-            b'L' => Dir::Left,
-            b'R' => Dir::Right,
-            _ => panic!("unexpected direction {}", dir as char),
-        };
-        result.push(Move { dir, count });
+pub struct Day;
+
+impl Solution for Day {
+    type Input<'a> = Vec<Move>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(s: &str) -> Result<Self::Input<'_>, ParseError> {
+        let p = Parser::new(s);
+        let mut result = Vec::with_capacity(2048);
+        for line in s.lines() {
+            let (dir, count) = p.split_once(line, " ")?;
+            let count = p.number(count)?;
+
+            let dir = match dir {
+                "U" => Dir4::Up,
+                "D" => Dir4::Down,
+                "L" => Dir4::Left,
+                "R" => Dir4::Right,
+                _ => return Err(p.error(dir, "a direction, U, D, L or R")),
+            };
+            result.push(Move { dir, count });
+        }
+        Ok(result)
     }
-    result
-}
 
-trait MoveVect {
-    fn move_toward(&mut self, other: Self) -> bool;
-    fn move_to(&mut self, dir: Dir);
-}
+    fn part_1(moves: &Self::Input<'_>) -> usize {
+        count_tail_positions::<2>(moves)
+    }
 
-impl MoveVect for Vect {
-    // return if move happened
-    fn move_toward(&mut self, other: Self) -> bool {
-        let x_diff = other.0 - self.0;
-        let y_diff = other.1 - self.1;
-        let dist = cmp::max(x_diff.abs(), y_diff.abs());
-        if dist <= 1 {
-            return false;
-        }
-        self.0 += x_diff.signum();
-        self.1 += y_diff.signum();
-        true
+    fn part_2(moves: &Self::Input<'_>) -> usize {
+        count_tail_positions::<10>(moves)
     }
+}
 
-    fn move_to(&mut self, dir: Dir) {
-        let diff = dir.to_vect();
-        self.0 += diff.0;
-        self.1 += diff.1;
+// return if move happened
+fn move_toward(knot: &mut Vect, other: Vect) -> bool {
+    if knot.chebyshev(other) <= 1 {
+        return false;
     }
+    let diff = other - *knot;
+    *knot += Vect::new(diff.x.signum(), diff.y.signum());
+    true
 }
 
 fn count_tail_positions<const KNOTS: usize>(moves: &[Move]) -> usize {
-    let mut knots = [(0, 0); KNOTS];
+    let mut knots = [Vect::default(); KNOTS];
 
     let mut tail_positions = HashSet::with_capacity(2048);
-    tail_positions.insert((0, 0));
+    tail_positions.insert(Vect::default());
     for m in moves {
         'head_move: for _ in 0..m.count {
-            knots[0].move_to(m.dir);
+            knots[0] += m.dir.offset();
             for i in 1..KNOTS {
-                if !knots[i].move_toward(knots[i - 1]) {
+                let leader = knots[i - 1];
+                if !move_toward(&mut knots[i], leader) {
                     continue 'head_move;
                 }
             }
@@ -96,22 +76,14 @@ fn count_tail_positions<const KNOTS: usize>(moves: &[Move]) -> usize {
     tail_positions.len()
 }
 
-pub fn part_1(moves: &[Move]) -> usize {
-    count_tail_positions::<2>(moves)
-}
-
-pub fn part_2(moves: &[Move]) -> usize {
-    count_tail_positions::<10>(moves)
-}
-
-super::day_test! {demo_1 == 13}
-super::day_test! {demo_2 == 1}
-super::day_test! {part_1 == 6498}
-super::day_test! {part_2 == 2531}
+super::day_test! {demo_1}
+super::day_test! {demo_2}
+super::day_test! {part_1}
+super::day_test! {part_2}
 
 #[test]
 fn demo2_2() {
     let input = "R 5\nU 8\nL 8\nD 3\nR 17\nD 10\nL 25\nU 20\n";
-    let input = generator(input);
-    assert_eq!(part_2(&input), 36);
+    let input = Day::parse(input).unwrap();
+    assert_eq!(Day::part_2(&input), 36);
 }
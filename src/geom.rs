@@ -0,0 +1,457 @@
+//! Points, and directions between them
+//!
+//! `y` grows downwards, like rows of a grid: [`Dir4::Up`] is `(0, -1)`.
+
+use std::fmt;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+/// An integer coordinate, with its signed and unsigned counterparts
+///
+/// The unchecked offsets panic on overflow in debug builds, and wrap in release builds.
+pub trait Coord: Copy + Ord + Add<Output = Self> + Sub<Output = Self> {
+    /// The type of distances between coordinates
+    type Unsigned: Copy + Ord + Add<Output = Self::Unsigned>;
+    /// The type of offsets from a coordinate
+    type Signed: Copy + From<i8>;
+
+    fn abs_diff(self, other: Self) -> Self::Unsigned;
+    fn checked_add_signed(self, offset: Self::Signed) -> Option<Self>;
+    fn checked_add_unsigned(self, offset: Self::Unsigned) -> Option<Self>;
+    fn checked_sub_unsigned(self, offset: Self::Unsigned) -> Option<Self>;
+    fn add_signed(self, offset: Self::Signed) -> Self;
+    fn add_unsigned(self, offset: Self::Unsigned) -> Self;
+    fn sub_unsigned(self, offset: Self::Unsigned) -> Self;
+}
+
+macro_rules! coord {
+    ($($unsigned:ident, $signed:ident);* $(;)?) => {$(
+        impl Coord for $unsigned {
+            type Unsigned = $unsigned;
+            type Signed = $signed;
+
+            #[inline(always)]
+            fn abs_diff(self, other: Self) -> $unsigned {
+                self.abs_diff(other)
+            }
+
+            #[inline(always)]
+            fn checked_add_signed(self, offset: $signed) -> Option<Self> {
+                self.checked_add_signed(offset)
+            }
+
+            #[inline(always)]
+            fn checked_add_unsigned(self, offset: $unsigned) -> Option<Self> {
+                self.checked_add(offset)
+            }
+
+            #[inline(always)]
+            fn checked_sub_unsigned(self, offset: $unsigned) -> Option<Self> {
+                self.checked_sub(offset)
+            }
+
+            #[inline(always)]
+            fn add_signed(self, offset: $signed) -> Self {
+                coord!(@debug_checked self.checked_add_signed(offset), self.wrapping_add_signed(offset))
+            }
+
+            #[inline(always)]
+            fn add_unsigned(self, offset: $unsigned) -> Self {
+                coord!(@debug_checked self.checked_add(offset), self.wrapping_add(offset))
+            }
+
+            #[inline(always)]
+            fn sub_unsigned(self, offset: $unsigned) -> Self {
+                coord!(@debug_checked self.checked_sub(offset), self.wrapping_sub(offset))
+            }
+        }
+
+        impl Coord for $signed {
+            type Unsigned = $unsigned;
+            type Signed = $signed;
+
+            #[inline(always)]
+            fn abs_diff(self, other: Self) -> $unsigned {
+                self.abs_diff(other)
+            }
+
+            #[inline(always)]
+            fn checked_add_signed(self, offset: $signed) -> Option<Self> {
+                self.checked_add(offset)
+            }
+
+            #[inline(always)]
+            fn checked_add_unsigned(self, offset: $unsigned) -> Option<Self> {
+                self.checked_add_unsigned(offset)
+            }
+
+            #[inline(always)]
+            fn checked_sub_unsigned(self, offset: $unsigned) -> Option<Self> {
+                self.checked_sub_unsigned(offset)
+            }
+
+            #[inline(always)]
+            fn add_signed(self, offset: $signed) -> Self {
+                coord!(@debug_checked self.checked_add(offset), self.wrapping_add(offset))
+            }
+
+            #[inline(always)]
+            fn add_unsigned(self, offset: $unsigned) -> Self {
+                coord!(@debug_checked self.checked_add_unsigned(offset), self.wrapping_add_unsigned(offset))
+            }
+
+            #[inline(always)]
+            fn sub_unsigned(self, offset: $unsigned) -> Self {
+                coord!(@debug_checked self.checked_sub_unsigned(offset), self.wrapping_sub_unsigned(offset))
+            }
+        }
+    )*};
+    (@debug_checked $checked:expr, $wrapping:expr) => {
+        if cfg!(debug_assertions) {
+            $checked.expect("coordinate overflow")
+        } else {
+            $wrapping
+        }
+    };
+}
+
+coord! {
+    u8, i8;
+    u16, i16;
+    u32, i32;
+    u64, i64;
+    usize, isize;
+}
+
+#[derive(Default, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point2<T> {
+    pub x: T,
+    pub y: T,
+}
+
+impl<T> Point2<T> {
+    pub const fn new(x: T, y: T) -> Self {
+        Self { x, y }
+    }
+}
+
+impl<T: Coord> Point2<T> {
+    /// The distance moving only orthogonally
+    pub fn manhattan(self, other: Self) -> T::Unsigned {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+    }
+
+    /// The distance moving diagonally as well
+    pub fn chebyshev(self, other: Self) -> T::Unsigned {
+        self.x.abs_diff(other.x).max(self.y.abs_diff(other.y))
+    }
+
+    /// `None` if either coordinate overflows, e.g. an unsigned point would become negative
+    pub fn checked_add_signed(self, offset: Point2<T::Signed>) -> Option<Self> {
+        Some(Self::new(
+            self.x.checked_add_signed(offset.x)?,
+            self.y.checked_add_signed(offset.y)?,
+        ))
+    }
+
+    pub fn add_signed(self, offset: Point2<T::Signed>) -> Self {
+        Self::new(self.x.add_signed(offset.x), self.y.add_signed(offset.y))
+    }
+
+    /// The neighbour in direction `dir`, if it's representable
+    pub fn checked_step(self, dir: Dir4) -> Option<Self> {
+        self.checked_add_signed(dir.offset())
+    }
+}
+
+impl<T> From<(T, T)> for Point2<T> {
+    fn from((x, y): (T, T)) -> Self {
+        Self::new(x, y)
+    }
+}
+
+impl<T> From<Point2<T>> for (T, T) {
+    fn from(point: Point2<T>) -> Self {
+        (point.x, point.y)
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Point2<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({:?}, {:?})", self.x, self.y)
+    }
+}
+
+#[derive(Default, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point3<T> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+impl<T> Point3<T> {
+    pub const fn new(x: T, y: T, z: T) -> Self {
+        Self { x, y, z }
+    }
+}
+
+impl<T: Coord> Point3<T> {
+    /// The distance moving only along the axes
+    pub fn manhattan(self, other: Self) -> T::Unsigned {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y) + self.z.abs_diff(other.z)
+    }
+
+    /// The distance moving diagonally as well
+    pub fn chebyshev(self, other: Self) -> T::Unsigned {
+        self.x
+            .abs_diff(other.x)
+            .max(self.y.abs_diff(other.y))
+            .max(self.z.abs_diff(other.z))
+    }
+
+    /// `None` if any coordinate overflows, e.g. an unsigned point would become negative
+    pub fn checked_add_signed(self, offset: Point3<T::Signed>) -> Option<Self> {
+        Some(Self::new(
+            self.x.checked_add_signed(offset.x)?,
+            self.y.checked_add_signed(offset.y)?,
+            self.z.checked_add_signed(offset.z)?,
+        ))
+    }
+
+    pub fn add_signed(self, offset: Point3<T::Signed>) -> Self {
+        Self::new(
+            self.x.add_signed(offset.x),
+            self.y.add_signed(offset.y),
+            self.z.add_signed(offset.z),
+        )
+    }
+}
+
+impl<T> From<[T; 3]> for Point3<T> {
+    fn from([x, y, z]: [T; 3]) -> Self {
+        Self::new(x, y, z)
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Point3<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({:?}, {:?}, {:?})", self.x, self.y, self.z)
+    }
+}
+
+macro_rules! point_ops {
+    ($point:ident { $($field:ident),* }) => {
+        impl<T: Add<Output = T>> Add for $point<T> {
+            type Output = Self;
+
+            fn add(self, rhs: Self) -> Self {
+                Self { $($field: self.$field + rhs.$field),* }
+            }
+        }
+
+        impl<T: Sub<Output = T>> Sub for $point<T> {
+            type Output = Self;
+
+            fn sub(self, rhs: Self) -> Self {
+                Self { $($field: self.$field - rhs.$field),* }
+            }
+        }
+
+        /// Scaled by a factor
+        impl<T: Mul<Output = T> + Copy> Mul<T> for $point<T> {
+            type Output = Self;
+
+            fn mul(self, rhs: T) -> Self {
+                Self { $($field: self.$field * rhs),* }
+            }
+        }
+
+        impl<T: Neg<Output = T>> Neg for $point<T> {
+            type Output = Self;
+
+            fn neg(self) -> Self {
+                Self { $($field: -self.$field),* }
+            }
+        }
+
+        impl<T: AddAssign> AddAssign for $point<T> {
+            fn add_assign(&mut self, rhs: Self) {
+                $(self.$field += rhs.$field;)*
+            }
+        }
+
+        impl<T: SubAssign> SubAssign for $point<T> {
+            fn sub_assign(&mut self, rhs: Self) {
+                $(self.$field -= rhs.$field;)*
+            }
+        }
+    };
+}
+
+point_ops!(Point2 { x, y });
+point_ops!(Point3 { x, y, z });
+
+/// One of the 4 orthogonal directions
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Dir4 {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Dir4 {
+    /// Clockwise, from up
+    pub const ALL: [Self; 4] = [Self::Up, Self::Right, Self::Down, Self::Left];
+
+    /// The offset of one step in this direction
+    #[inline(always)]
+    pub fn offset<T: From<i8>>(self) -> Point2<T> {
+        let (x, y) = match self {
+            Self::Up => (0, -1),
+            Self::Right => (1, 0),
+            Self::Down => (0, 1),
+            Self::Left => (-1, 0),
+        };
+        Point2::new(x.into(), y.into())
+    }
+
+    /// Rotate by 90° clockwise
+    pub fn turn_right(self) -> Self {
+        Self::ALL[(self as usize + 1) % 4]
+    }
+
+    /// Rotate by 90° counterclockwise
+    pub fn turn_left(self) -> Self {
+        Self::ALL[(self as usize + 3) % 4]
+    }
+
+    pub fn reverse(self) -> Self {
+        Self::ALL[(self as usize + 2) % 4]
+    }
+}
+
+/// One of the 8 directions, including diagonals
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Dir8 {
+    Up,
+    UpRight,
+    Right,
+    DownRight,
+    Down,
+    DownLeft,
+    Left,
+    UpLeft,
+}
+
+impl Dir8 {
+    /// Clockwise, from up
+    pub const ALL: [Self; 8] = [
+        Self::Up,
+        Self::UpRight,
+        Self::Right,
+        Self::DownRight,
+        Self::Down,
+        Self::DownLeft,
+        Self::Left,
+        Self::UpLeft,
+    ];
+
+    /// The offset of one step in this direction
+    #[inline(always)]
+    pub fn offset<T: From<i8>>(self) -> Point2<T> {
+        let (x, y) = match self {
+            Self::Up => (0, -1),
+            Self::UpRight => (1, -1),
+            Self::Right => (1, 0),
+            Self::DownRight => (1, 1),
+            Self::Down => (0, 1),
+            Self::DownLeft => (-1, 1),
+            Self::Left => (-1, 0),
+            Self::UpLeft => (-1, -1),
+        };
+        Point2::new(x.into(), y.into())
+    }
+
+    /// Rotate by 45° clockwise
+    pub fn turn_right(self) -> Self {
+        Self::ALL[(self as usize + 1) % 8]
+    }
+
+    /// Rotate by 45° counterclockwise
+    pub fn turn_left(self) -> Self {
+        Self::ALL[(self as usize + 7) % 8]
+    }
+
+    pub fn reverse(self) -> Self {
+        Self::ALL[(self as usize + 4) % 8]
+    }
+}
+
+impl From<Dir4> for Dir8 {
+    fn from(dir: Dir4) -> Self {
+        Self::ALL[dir as usize * 2]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn points() {
+        let a = Point2::new(1, -2);
+        let b = Point2::new(-3, 4);
+        assert_eq!(a + b, Point2::new(-2, 2));
+        assert_eq!(a - b, Point2::new(4, -6));
+        assert_eq!(-a, Point2::new(-1, 2));
+        assert_eq!(a * 3, Point2::new(3, -6));
+        assert_eq!(a.manhattan(b), 10u32);
+        assert_eq!(a.chebyshev(b), 6u32);
+        assert_eq!(Point2::from((1, -2)), a);
+        assert_eq!(<(i32, i32)>::from(b), (-3, 4));
+
+        let mut c = Point3::new(1u8, 2, 3);
+        c += Point3::new(1, 1, 1);
+        assert_eq!(c, Point3::from([2, 3, 4]));
+        assert_eq!(c.manhattan(Point3::new(0, 0, 0)), 9);
+        assert_eq!(c.chebyshev(Point3::new(0, 0, 0)), 4);
+        assert_eq!(
+            c.checked_add_signed(Point3::new(-2, 0, 1)),
+            Some(Point3::new(0, 3, 5))
+        );
+        assert_eq!(c.checked_add_signed(Point3::new(0, -4, 0)), None);
+        assert_eq!(c.add_signed(Point3::new(-1, -1, -1)), Point3::new(1, 2, 3));
+    }
+
+    #[test]
+    fn offsets() {
+        assert_eq!(5i32.add_unsigned(3), 8);
+        assert_eq!(5i32.sub_unsigned(8), -3);
+        assert_eq!(5u32.add_signed(-5), 0);
+        assert_eq!(i8::MIN.checked_sub_unsigned(1), None);
+        assert_eq!(0usize.checked_add_signed(-1), None);
+        assert_eq!(Point2::new(0u16, 0).checked_step(Dir4::Up), None);
+        assert_eq!(
+            Point2::new(0u16, 0).checked_step(Dir4::Right),
+            Some(Point2::new(1, 0))
+        );
+    }
+
+    #[test]
+    fn directions() {
+        for dir in Dir4::ALL {
+            assert_eq!(dir.turn_right().turn_left(), dir);
+            assert_eq!(dir.turn_right().turn_right(), dir.reverse());
+            assert_eq!(dir.reverse().offset::<i32>(), -dir.offset::<i32>());
+            assert_eq!(Dir8::from(dir).offset::<i32>(), dir.offset());
+        }
+        assert_eq!(Dir4::Left.turn_right(), Dir4::Up);
+        for dir in Dir8::ALL {
+            assert_eq!(dir.turn_left().turn_right(), dir);
+            assert_eq!(dir.reverse().offset::<i32>(), -dir.offset::<i32>());
+            // Each turn moves to an adjacent offset
+            let (a, b) = (dir.offset::<i32>(), dir.turn_right().offset::<i32>());
+            assert_eq!(a.chebyshev(b), 1);
+        }
+    }
+}
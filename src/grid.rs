@@ -1,37 +1,61 @@
+use crate::geom::{Dir4, Dir8, Point2};
+use bitvec::prelude::*;
 use std::fmt;
 use std::fmt::Write;
+use std::ops::{Index, IndexMut};
 
 // Newline Seperated Grid
-#[derive(Copy, Clone)]
+//
+// Lines may be different lengths: `width` is the length of the longest line, and positions past
+// the end of a shorter line are out of bounds. When every line is as long, cells are found by
+// their stride, without indexing the lines.
+#[derive(Clone)]
 pub struct NlGrid<'a> {
     width: usize,
     data: &'a [u8],
+    // Where each line starts, followed by one past the end of the last line, only for ragged lines
+    line_starts: Option<Vec<usize>>,
 }
 
 impl<'a> NlGrid<'a> {
+    /// Empty input is an empty grid
     pub fn new(data: &'a str) -> Self {
-        let width = data.find('\n').unwrap();
+        let first_len = data.find('\n').unwrap_or(data.len());
+        let line_starts = if data
+            .split_terminator('\n')
+            .all(|line| line.len() == first_len)
+        {
+            None
+        } else {
+            let mut starts = vec![0];
+            starts.extend(data.match_indices('\n').map(|(i, _)| i + 1));
+            if !data.ends_with('\n') {
+                starts.push(data.len() + 1);
+            }
+            Some(starts)
+        };
+        let width = match &line_starts {
+            Some(starts) => starts.windows(2).map(|w| w[1] - w[0] - 1).max().unwrap(),
+            None => first_len,
+        };
         Self {
             width,
             data: data.as_bytes(),
+            line_starts,
         }
     }
 
-    fn idx(&self, x: i32, y: i32) -> Option<usize> {
-        if x < 0 || y < 0 || x > self.width as i32 {
-            return None;
-        }
-        let idx = (y * (self.width + 1) as i32 + x).try_into().ok()?;
-        if idx >= self.data.len() {
-            return None;
-        }
-
-        Some(idx)
-    }
-
     pub fn get(&self, x: i32, y: i32) -> Option<u8> {
-        let idx = self.idx(x, y)?;
-        Some(self.data[idx])
+        let x = usize::try_from(x).ok()?;
+        let y = usize::try_from(y).ok()?;
+        match &self.line_starts {
+            Some(starts) => {
+                let (start, next) = (*starts.get(y)?, *starts.get(y + 1)?);
+                (x < next - start - 1).then(|| self.data[start + x])
+            }
+            None if x < self.width => self.data.get(y * (self.width + 1) + x).copied(),
+            None => None,
+        }
     }
 
     pub fn width(&self) -> usize {
@@ -39,34 +63,524 @@ impl<'a> NlGrid<'a> {
     }
 
     pub fn height(&self) -> usize {
-        (self.data.len() + 1) / (self.width + 1)
+        match &self.line_starts {
+            Some(starts) => starts.len() - 1,
+            None => self.data.len().div_ceil(self.width + 1),
+        }
     }
 
     pub fn position_of(&self, byte: u8) -> Option<(i32, i32)> {
         let i = self.data.iter().position(|&b| b == byte)?;
-
-        let stride = self.width + 1;
-        Some(((i % stride) as i32, (i / stride) as i32))
+        Some(self.position(i))
     }
 
     pub fn multi_position(&self, byte: u8) -> impl Iterator<Item = (i32, i32)> + '_ {
-        let stride = self.width + 1;
-
         self.data
             .iter()
             .enumerate()
             .filter_map(move |(i, &b)| (b == byte).then_some(i))
-            .map(move |i| ((i % stride) as i32, (i / stride) as i32))
+            .map(|i| self.position(i))
+    }
+
+    // The position of the byte at `i` in the data
+    fn position(&self, i: usize) -> (i32, i32) {
+        let (x, y) = match &self.line_starts {
+            Some(starts) => {
+                let y = starts.partition_point(|&start| start <= i) - 1;
+                (i - starts[y], y)
+            }
+            None => (i % (self.width + 1), i / (self.width + 1)),
+        };
+        (x as i32, y as i32)
     }
 }
 
 impl<'a> fmt::Debug for NlGrid<'a> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let stride = self.width + 1;
-        for row in self.data.chunks(stride) {
+        for line in self.data.split(|&b| b == b'\n').take(self.height()) {
+            f.write_char('\n')?;
+            f.write_str(std::str::from_utf8(line).unwrap())?;
+        }
+        Ok(())
+    }
+}
+
+/// The positions `offsets` away from `(x, y)` which are inside a `width` by `height` grid
+fn neighbors(
+    width: usize,
+    height: usize,
+    (x, y): (usize, usize),
+    offsets: impl Iterator<Item = Point2<isize>>,
+) -> impl Iterator<Item = (usize, usize)> {
+    offsets.filter_map(move |offset| {
+        let Point2 { x, y } = Point2::new(x, y).checked_add_signed(offset)?;
+        (x < width && y < height).then_some((x, y))
+    })
+}
+
+// An owned grid, stored row by row
+//
+// Positions are `(x, y)`, with `y` growing downwards. Indexing with a position out of bounds
+// panics, `get` returns `None` instead.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// A grid of `width` columns, from its cells row by row
+    ///
+    /// Panics if `cells` doesn't hold a whole number of rows. A grid 0 wide has no rows, use
+    /// [`Grid::filled`] for empty rows.
+    pub fn from_vec(width: usize, cells: Vec<T>) -> Self {
+        let height = cells.len().checked_div(width).unwrap_or(0);
+        assert_eq!(width * height, cells.len(), "not a whole number of rows");
+        Self {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    pub fn filled(width: usize, height: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn in_bounds(&self, x: usize, y: usize) -> bool {
+        x < self.width && y < self.height
+    }
+
+    fn idx(&self, x: usize, y: usize) -> Option<usize> {
+        self.in_bounds(x, y).then_some(y * self.width + x)
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<&T> {
+        self.cells.get(self.idx(x, y)?)
+    }
+
+    pub fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut T> {
+        let idx = self.idx(x, y)?;
+        self.cells.get_mut(idx)
+    }
+
+    /// # Safety
+    ///
+    /// `(x, y)` must be in bounds.
+    pub unsafe fn get_unchecked(&self, x: usize, y: usize) -> &T {
+        debug_assert!(self.in_bounds(x, y));
+        self.cells.get_unchecked(y * self.width + x)
+    }
+
+    /// Panics if `(x, y)` is out of bounds
+    pub fn set(&mut self, x: usize, y: usize, value: T) {
+        self[(x, y)] = value;
+    }
+
+    /// Every cell, row by row
+    pub fn cells(&self) -> &[T] {
+        &self.cells
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        assert!(y < self.height, "row {y} out of bounds");
+        &self.cells[y * self.width..][..self.width]
+    }
+
+    pub fn rows(&self) -> impl DoubleEndedIterator<Item = &[T]> + '_ {
+        // Not `chunks_exact`, since rows can be empty
+        (0..self.height).map(|y| self.row(y))
+    }
+
+    /// The cells of column `x`, from the top
+    pub fn column(&self, x: usize) -> impl DoubleEndedIterator<Item = &T> + '_ {
+        assert!(x < self.width, "column {x} out of bounds");
+        // Without any rows, there are no cells from `x` on
+        self.cells
+            .get(x..)
+            .unwrap_or_default()
+            .iter()
+            .step_by(self.width)
+    }
+
+    /// The positions of the up to 4 orthogonal neighbours of `(x, y)`
+    pub fn neighbors4(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> {
+        neighbors(
+            self.width,
+            self.height,
+            (x, y),
+            Dir4::ALL.map(Dir4::offset).into_iter(),
+        )
+    }
+
+    /// The positions of the up to 8 neighbours of `(x, y)`, including diagonals
+    pub fn neighbors8(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> {
+        neighbors(
+            self.width,
+            self.height,
+            (x, y),
+            Dir8::ALL.map(Dir8::offset).into_iter(),
+        )
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (x, y): (usize, usize)) -> &T {
+        match self.idx(x, y) {
+            Some(idx) => &self.cells[idx],
+            None => panic!(
+                "({x}, {y}) out of bounds of a {}x{} grid",
+                self.width, self.height
+            ),
+        }
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut T {
+        match self.idx(x, y) {
+            Some(idx) => &mut self.cells[idx],
+            None => panic!(
+                "({x}, {y}) out of bounds of a {}x{} grid",
+                self.width, self.height
+            ),
+        }
+    }
+}
+
+// A grid of bools, packed into bits
+//
+// Unlike `Grid`, rows can be added (or removed) with `resize`.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct BitGrid {
+    width: usize,
+    height: usize,
+    bits: BitVec,
+}
+
+impl BitGrid {
+    /// An empty (all false) grid
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            bits: bitvec![0; width * height],
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    #[inline]
+    pub fn in_bounds(&self, x: usize, y: usize) -> bool {
+        x < self.width && y < self.height
+    }
+
+    #[inline]
+    fn idx(&self, x: usize, y: usize) -> Option<usize> {
+        self.in_bounds(x, y).then_some(y * self.width + x)
+    }
+
+    // The word holding bit `idx`, and the mask of the bit in it
+    //
+    // Reading and writing whole words is quicker than going through `BitSlice` for single bits.
+    // The bits always start at the start of the first word, as they're never sliced from the front.
+    #[inline]
+    fn word(idx: usize) -> (usize, usize) {
+        (
+            idx / usize::BITS as usize,
+            1 << (idx % usize::BITS as usize),
+        )
+    }
+
+    /// # Safety
+    ///
+    /// `idx` must be in bounds.
+    #[inline]
+    unsafe fn bit(&self, idx: usize) -> bool {
+        debug_assert!(idx < self.bits.len());
+        let (word, mask) = Self::word(idx);
+        self.bits.as_raw_slice().get_unchecked(word) & mask != 0
+    }
+
+    #[inline]
+    pub fn get(&self, x: usize, y: usize) -> Option<bool> {
+        let idx = self.idx(x, y)?;
+        // SAFETY: `idx` checks the bounds
+        Some(unsafe { self.bit(idx) })
+    }
+
+    /// # Safety
+    ///
+    /// `(x, y)` must be in bounds.
+    #[inline]
+    pub unsafe fn get_unchecked(&self, x: usize, y: usize) -> bool {
+        debug_assert!(self.in_bounds(x, y));
+        self.bit(y * self.width + x)
+    }
+
+    /// Panics if `(x, y)` is out of bounds
+    #[inline]
+    pub fn set(&mut self, x: usize, y: usize, value: bool) {
+        let Some(idx) = self.idx(x, y) else {
+            self.out_of_bounds(x, y)
+        };
+        let (word, mask) = Self::word(idx);
+        // SAFETY: `idx` checks the bounds
+        let word = unsafe { self.bits.as_raw_mut_slice().get_unchecked_mut(word) };
+        if value {
+            *word |= mask;
+        } else {
+            *word &= !mask;
+        }
+    }
+
+    /// Add empty rows to (or remove rows from) the bottom, to have `height` rows
+    pub fn resize(&mut self, height: usize) {
+        self.height = height;
+        self.bits.resize(height * self.width, false);
+    }
+
+    /// Every cell, row by row
+    pub fn bits(&self) -> &BitSlice {
+        &self.bits
+    }
+
+    pub fn row(&self, y: usize) -> &BitSlice {
+        assert!(y < self.height, "row {y} out of bounds");
+        &self.bits[y * self.width..][..self.width]
+    }
+
+    pub fn rows(&self) -> impl DoubleEndedIterator<Item = &BitSlice> + '_ {
+        // Not `chunks_exact`, since rows can be empty
+        (0..self.height).map(|y| self.row(y))
+    }
+
+    /// The cells of column `x`, from the top
+    pub fn column(&self, x: usize) -> impl DoubleEndedIterator<Item = bool> + '_ {
+        assert!(x < self.width, "column {x} out of bounds");
+        let bits = self.bits.get(x..).unwrap_or_default();
+        bits.iter().by_vals().step_by(self.width)
+    }
+
+    pub fn count_ones(&self) -> usize {
+        self.bits.count_ones()
+    }
+
+    /// The positions of the true cells, row by row
+    pub fn iter_ones(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.bits
+            .iter_ones()
+            .map(|idx| (idx % self.width, idx / self.width))
+    }
+
+    /// The positions of the up to 4 orthogonal neighbours of `(x, y)`
+    pub fn neighbors4(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> {
+        neighbors(
+            self.width,
+            self.height,
+            (x, y),
+            Dir4::ALL.map(Dir4::offset).into_iter(),
+        )
+    }
+
+    /// The positions of the up to 8 neighbours of `(x, y)`, including diagonals
+    pub fn neighbors8(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> {
+        neighbors(
+            self.width,
+            self.height,
+            (x, y),
+            Dir8::ALL.map(Dir8::offset).into_iter(),
+        )
+    }
+
+    fn out_of_bounds(&self, x: usize, y: usize) -> ! {
+        panic!(
+            "({x}, {y}) out of bounds of a {}x{} grid",
+            self.width,
+            self.height()
+        )
+    }
+}
+
+impl Index<(usize, usize)> for BitGrid {
+    type Output = bool;
+
+    fn index(&self, (x, y): (usize, usize)) -> &bool {
+        match self.get(x, y) {
+            Some(true) => &true,
+            Some(false) => &false,
+            None => self.out_of_bounds(x, y),
+        }
+    }
+}
+
+impl fmt::Debug for BitGrid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.rows() {
             f.write_char('\n')?;
-            f.write_str(std::str::from_utf8(&row[..self.width]).unwrap())?;
+            for bit in row.iter().by_vals() {
+                f.write_char(if bit { '#' } else { '.' })?;
+            }
         }
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nl_grid() {
+        for input in ["ab\ncd\n", "ab\ncd"] {
+            let grid = NlGrid::new(input);
+            assert_eq!((grid.width(), grid.height()), (2, 2));
+            assert_eq!(grid.get(1, 1), Some(b'd'));
+            assert_eq!(grid.get(2, 0), None);
+            assert_eq!(grid.get(0, 2), None);
+            assert_eq!(grid.position_of(b'c'), Some((0, 1)));
+            assert_eq!(format!("{grid:?}"), "\nab\ncd");
+        }
+
+        let ragged = NlGrid::new("  a\nbcde\n\nf\n");
+        assert_eq!((ragged.width(), ragged.height()), (4, 4));
+        assert_eq!(ragged.get(2, 0), Some(b'a'));
+        assert_eq!(ragged.get(3, 0), None);
+        assert_eq!(ragged.get(3, 1), Some(b'e'));
+        assert_eq!(ragged.get(0, 2), None);
+        assert_eq!(ragged.get(0, 3), Some(b'f'));
+        assert_eq!(ragged.get(0, 4), None);
+        assert_eq!(ragged.position_of(b'd'), Some((2, 1)));
+        assert_eq!(ragged.multi_position(b'f').collect::<Vec<_>>(), [(0, 3)]);
+        assert_eq!(format!("{ragged:?}"), "\n  a\nbcde\n\nf");
+
+        let empty = NlGrid::new("");
+        assert_eq!((empty.width(), empty.height()), (0, 0));
+        assert_eq!(empty.get(0, 0), None);
+        assert_eq!(empty.position_of(b'a'), None);
+    }
+
+    #[test]
+    fn grid() {
+        let mut grid = Grid::from_vec(3, (0..6).collect());
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid.get(2, 1), Some(&5));
+        assert_eq!(grid.get(3, 0), None);
+        assert_eq!(grid.get(0, 2), None);
+        grid.set(1, 0, 10);
+        *grid.get_mut(0, 1).unwrap() += 10;
+        assert_eq!(grid[(1, 0)], 10);
+        assert_eq!(unsafe { *grid.get_unchecked(0, 1) }, 13);
+
+        assert_eq!(grid.row(1), [13, 4, 5]);
+        assert_eq!(grid.rows().collect::<Vec<_>>(), [[0, 10, 2], [13, 4, 5]]);
+        assert!(grid.column(1).eq(&[10, 4]));
+        assert!(grid.column(2).rev().eq(&[5, 2]));
+
+        let empty = Grid::<u8>::from_vec(0, vec![]);
+        assert_eq!((empty.height(), empty.rows().count()), (0, 0));
+    }
+
+    #[test]
+    fn zero_size_grids() {
+        let narrow = Grid::filled(0, 3, 0u8);
+        assert_eq!((narrow.width(), narrow.height()), (0, 3));
+        assert!(narrow.rows().all(<[u8]>::is_empty));
+        assert_eq!(narrow.rows().count(), 3);
+        assert_eq!(narrow.get(0, 0), None);
+
+        let flat = Grid::filled(3, 0, 0u8);
+        assert_eq!((flat.width(), flat.height()), (3, 0));
+        assert_eq!(flat.rows().count(), 0);
+        assert_eq!(flat.column(2).count(), 0);
+
+        let bits = BitGrid::new(3, 0);
+        assert_eq!(bits.column(1).count(), 0);
+        let bits = BitGrid::new(0, 2);
+        assert_eq!((bits.width(), bits.height()), (0, 2));
+        assert!(bits.rows().all(|row| row.is_empty()));
+        assert_eq!(bits.rows().count(), 2);
+        assert_eq!(bits.get(0, 0), None);
+    }
+
+    #[test]
+    #[should_panic = "row 2 out of bounds"]
+    fn grid_row_out_of_bounds() {
+        Grid::filled(0, 2, 0).row(2);
+    }
+
+    #[test]
+    #[should_panic = "row 2 out of bounds"]
+    fn bit_grid_row_out_of_bounds() {
+        BitGrid::new(3, 2).row(2);
+    }
+
+    #[test]
+    #[should_panic = "(3, 0) out of bounds of a 3x2 grid"]
+    fn grid_out_of_bounds() {
+        Grid::filled(3, 2, 0).set(3, 0, 1);
+    }
+
+    #[test]
+    fn neighbors() {
+        let grid = Grid::filled(3, 3, ());
+        let sorted = |it: &mut dyn Iterator<Item = (usize, usize)>| {
+            let mut v: Vec<_> = it.collect();
+            v.sort_unstable();
+            v
+        };
+        assert_eq!(sorted(&mut grid.neighbors4(0, 0)), [(0, 1), (1, 0)]);
+        assert_eq!(grid.neighbors4(1, 1).count(), 4);
+        assert_eq!(sorted(&mut grid.neighbors8(2, 0)), [(1, 0), (1, 1), (2, 1)]);
+        assert_eq!(grid.neighbors8(1, 1).count(), 8);
+
+        let bits = BitGrid::new(1, 2);
+        assert_eq!(sorted(&mut bits.neighbors8(0, 0)), [(0, 1)]);
+    }
+
+    #[test]
+    fn bit_grid() {
+        let mut bits = BitGrid::new(3, 2);
+        bits.set(1, 0, true);
+        bits.set(2, 1, true);
+        assert_eq!(bits.get(1, 0), Some(true));
+        assert_eq!(bits.get(0, 0), Some(false));
+        assert_eq!(bits.get(0, 2), None);
+        assert!(bits[(2, 1)]);
+        assert!(!unsafe { bits.get_unchecked(2, 0) });
+        assert_eq!(bits.count_ones(), 2);
+        assert_eq!(bits.iter_ones().collect::<Vec<_>>(), [(1, 0), (2, 1)]);
+        assert!(bits.column(2).eq([false, true]));
+        assert_eq!(format!("{bits:?}"), "\n.#.\n..#");
+
+        bits.resize(3);
+        assert_eq!(bits.height(), 3);
+        assert_eq!(bits.row(2).count_ones(), 0);
+        bits.resize(1);
+        assert_eq!(bits.count_ones(), 1);
+    }
+}
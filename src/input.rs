@@ -0,0 +1,155 @@
+use crate::crypt;
+use std::borrow::Cow;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::{fmt, fs, io};
+
+/// Something [`normalize`] changed about an input
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Change {
+    ByteOrderMark,
+    CrlfLineEndings,
+    TrailingWhitespace,
+    MissingTrailingNewline,
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::ByteOrderMark => "removed byte order mark",
+            Self::CrlfLineEndings => "converted CRLF line endings to LF",
+            Self::TrailingWhitespace => "removed trailing whitespace",
+            Self::MissingTrailingNewline => "added missing trailing newline",
+        })
+    }
+}
+
+/// Put an input into the layout the generators expect: no byte order mark, `\n` line endings,
+/// and ending in exactly one `\n`
+///
+/// Whitespace at the end of each line is left alone, it's significant for some days (e.g. the
+/// maps in days 5 and 22).
+pub fn normalize(input: &str) -> (Cow<'_, str>, Vec<Change>) {
+    let mut changes = Vec::new();
+    let mut input = Cow::Borrowed(input);
+
+    if let Some(rest) = input.strip_prefix('\u{feff}') {
+        changes.push(Change::ByteOrderMark);
+        input = Cow::Owned(rest.to_string());
+    }
+    if input.contains("\r\n") {
+        changes.push(Change::CrlfLineEndings);
+        input = Cow::Owned(input.replace("\r\n", "\n"));
+    }
+
+    let trimmed_len = input.trim_end().len();
+    let rest = &input[trimmed_len..];
+    if trimmed_len == 0 || rest == "\n" {
+        return (input, changes);
+    }
+    if !rest.ends_with('\n') {
+        changes.push(Change::MissingTrailingNewline);
+    }
+    if !rest.strip_suffix('\n').unwrap_or(rest).is_empty() {
+        changes.push(Change::TrailingWhitespace);
+    }
+    let mut owned = input.into_owned();
+    owned.truncate(trimmed_len);
+    owned.push('\n');
+    (Cow::Owned(owned), changes)
+}
+
+/// The path which means stdin rather than a file
+pub const STDIN: &str = "-";
+
+/// Read and [`normalize`] the input at `path` (or stdin, for [`STDIN`]), warning on stderr if
+/// anything was changed
+///
+/// If there's only an encrypted version of the file, it's decrypted, see [`crypt`].
+pub fn read_input(path: &Path) -> io::Result<String> {
+    let input = if path == Path::new(STDIN) {
+        let mut input = String::new();
+        io::stdin().read_to_string(&mut input)?;
+        input
+    } else if !path.exists() && crypt::encrypted_path(path).exists() {
+        crypt::read_encrypted(path)?
+    } else {
+        fs::read_to_string(path)?
+    };
+    let (normalized, changes) = normalize(&input);
+    if !changes.is_empty() {
+        let changes: Vec<_> = changes.iter().map(Change::to_string).collect();
+        eprintln!("warning: {}: {}", display_path(path), changes.join(", "));
+    }
+    Ok(normalized.into_owned())
+}
+
+/// Whether there's an input at `path`, an empty file is only a placeholder for one, e.g. from
+/// `new`
+pub fn is_filled(path: &Path) -> bool {
+    fs::metadata(path).is_ok_and(|metadata| metadata.len() > 0)
+}
+
+/// The directory of this crate's sources, which relative input paths are in when not run from it
+pub fn crate_dir() -> &'static Path {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+}
+
+/// The directory holding a year's inputs, and its `answers.toml`
+pub fn year_dir(year: u32) -> PathBuf {
+    PathBuf::from(format!("input/{year}"))
+}
+
+/// The default path of a day's real (or demo) input, e.g. `input/2022/day19.txt`
+pub fn day_path(year: u32, day: u32, demo: bool) -> PathBuf {
+    let prefix = if demo { "demo" } else { "day" };
+    year_dir(year).join(format!("{prefix}{day}.txt"))
+}
+
+/// How to refer to an input's path in messages
+pub fn display_path(path: &Path) -> Cow<'_, str> {
+    if path == Path::new(STDIN) {
+        Cow::Borrowed("<stdin>")
+    } else {
+        path.to_string_lossy()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_inputs() {
+        assert_eq!(normalize("a\nb\n"), (Cow::Borrowed("a\nb\n"), vec![]));
+        assert_eq!(normalize(""), (Cow::Borrowed(""), vec![]));
+        // Trailing spaces are only removed from the end of the input
+        assert_eq!(normalize("  [D] \n[Z] [M]\n").1, vec![]);
+
+        assert_eq!(
+            normalize("\u{feff}a\r\nb\r\n"),
+            (
+                Cow::Borrowed("a\nb\n"),
+                vec![Change::ByteOrderMark, Change::CrlfLineEndings]
+            )
+        );
+        assert_eq!(
+            normalize("a\nb"),
+            (
+                Cow::Borrowed("a\nb\n"),
+                vec![Change::MissingTrailingNewline]
+            )
+        );
+        assert_eq!(
+            normalize("a\nb\n\n  \n"),
+            (Cow::Borrowed("a\nb\n"), vec![Change::TrailingWhitespace])
+        );
+        assert_eq!(
+            normalize("a\nb  "),
+            (
+                Cow::Borrowed("a\nb\n"),
+                vec![Change::MissingTrailingNewline, Change::TrailingWhitespace]
+            )
+        );
+    }
+}
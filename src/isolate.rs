@@ -0,0 +1,485 @@
+use crate::parse::ParseError;
+use crate::{panic_message, DayResult, DayRunner, GenError, PartResult, Parts};
+use clap::ValueEnum;
+use std::cell::RefCell;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::process::{self, Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::{Duration, Instant};
+use std::{env, panic, thread};
+
+/// If set, the current process is a child which should run a single day, and report back
+const CHILD_DAY_ENV: &str = "AOC_2022_ISOLATED_DAY";
+/// The year of the day the child should run
+const CHILD_YEAR_ENV: &str = "AOC_2022_ISOLATED_YEAR";
+/// Which parts the child should run
+const CHILD_PARTS_ENV: &str = "AOC_2022_ISOLATED_PARTS";
+/// How many times the child should run the day, reporting each run
+const CHILD_RUNS_ENV: &str = "AOC_2022_ISOLATED_RUNS";
+/// Marks the lines the child reports its results on, which share stdout with whatever the day
+/// prints itself
+const RECORD_MARKER: &str = "\u{1e}aoc ";
+
+/// True if a panic can't be caught in-process (`panic = "abort"`), so each day needs to be run in
+/// its own process to be able to report a panic and keep going
+pub const PANICS_ABORT: bool = cfg!(panic = "abort");
+
+/// The part of a day being run
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Phase {
+    Generator,
+    Part1,
+    Part2,
+}
+
+impl Phase {
+    const ALL: [Self; 3] = [Self::Generator, Self::Part1, Self::Part2];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Generator => "generator",
+            Self::Part1 => "part_1",
+            Self::Part2 => "part_2",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|phase| phase.name() == name)
+    }
+}
+
+static IS_CHILD: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+    static CHECKPOINT: RefCell<Option<(DayResult, Phase, Instant)>> = const { RefCell::new(None) };
+}
+
+/// Record that `phase` is about to start, with `result` holding the results so far
+///
+/// In a child process, this is reported back to the parent, so if the phase panics or times out,
+/// everything up to that point is still reported.
+pub fn checkpoint(result: &DayResult, phase: Phase) {
+    if IS_CHILD.load(Ordering::Relaxed) {
+        CHECKPOINT.with(|c| *c.borrow_mut() = Some((result.clone(), phase, Instant::now())));
+        print!("{}", encode(result, Some(phase)));
+        let _ = io::stdout().flush();
+    }
+}
+
+/// Run a day, in a child process if panics can't be caught in this one, or if the day needs to
+/// be stopped after `timeout`
+pub fn run_day(
+    runner: DayRunner,
+    year: u32,
+    day: u32,
+    input: &str,
+    parts: Parts,
+    timeout: Option<Duration>,
+) -> io::Result<DayResult> {
+    let mut results = run_day_repeated(runner, year, day, input, parts, timeout, 1)?;
+    Ok(results.pop().unwrap())
+}
+
+/// Run a day `runs` times, like [`run_day`], but all in the same child process if it's isolated,
+/// so later runs are timed in a process which has already been warmed up by the earlier ones
+///
+/// If a run fails in the child, it's the last one returned, there's always at least one.
+pub fn run_day_repeated(
+    runner: DayRunner,
+    year: u32,
+    day: u32,
+    input: &str,
+    parts: Parts,
+    timeout: Option<Duration>,
+    runs: u32,
+) -> io::Result<Vec<DayResult>> {
+    if PANICS_ABORT || timeout.is_some() {
+        run_isolated(year, day, input, parts, timeout, runs)
+    } else {
+        Ok((0..runs.max(1)).map(|_| runner(input, parts)).collect())
+    }
+}
+
+/// Run a day `runs` times in a child copy of the current executable, killing it if a run takes
+/// longer than `timeout`
+///
+/// Returns the result of each run, until one fails. The executable must call
+/// [`run_child_if_requested`] before doing anything else.
+pub fn run_isolated(
+    year: u32,
+    day: u32,
+    input: &str,
+    parts: Parts,
+    timeout: Option<Duration>,
+    runs: u32,
+) -> io::Result<Vec<DayResult>> {
+    let start = Instant::now();
+    let runs = runs.max(1);
+    let parts = parts.to_possible_value().unwrap();
+    let mut child = Command::new(env::current_exe()?)
+        .env(CHILD_YEAR_ENV, year.to_string())
+        .env(CHILD_DAY_ENV, day.to_string())
+        .env(CHILD_PARTS_ENV, parts.get_name())
+        .env(CHILD_RUNS_ENV, runs.to_string())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()?;
+    // The child reads all of its input before writing anything, and stdin is closed when dropped
+    child.stdin.take().unwrap().write_all(input.as_bytes())?;
+
+    let stdout = child.stdout.take().unwrap();
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        for line in BufReader::new(stdout).lines() {
+            let Ok(line) = line else { break };
+            if tx.send((line, Instant::now())).is_err() {
+                break;
+            }
+        }
+    });
+
+    let mut output = String::new();
+    // When the last complete record was received, to time the phase still running
+    let mut last_record = start;
+    // When the last run finished, to time the run still going
+    let mut run_start = start;
+    // Whether the record being received is of a run still going
+    let mut partial = false;
+    let timed_out = loop {
+        let received = match timeout {
+            Some(timeout) => rx.recv_timeout(timeout.saturating_sub(run_start.elapsed())),
+            None => rx.recv().map_err(RecvTimeoutError::from),
+        };
+        match received {
+            Ok((line, received_at)) => {
+                match record(&line) {
+                    Some("end") if !partial => {
+                        (last_record, run_start) = (received_at, received_at)
+                    }
+                    Some("end") => (last_record, partial) = (received_at, false),
+                    Some(record) if record.starts_with("running ") => partial = true,
+                    _ => {}
+                }
+                output.push_str(&line);
+                output.push('\n');
+            }
+            Err(RecvTimeoutError::Disconnected) => break false,
+            Err(RecvTimeoutError::Timeout) => {
+                child.kill()?;
+                break true;
+            }
+        }
+    };
+    let status = child.wait()?;
+
+    let mut results = Vec::with_capacity(runs as usize);
+    // The progress of the run still going, if any
+    let mut running = None;
+    for (result, phase) in decode(&output) {
+        match phase {
+            Some(phase) => running = Some((result, phase)),
+            None => {
+                results.push(result);
+                running = None;
+            }
+        }
+    }
+    // A child which panicked with `panic = "abort"` has already reported the failed run itself
+    if results.len() < runs as usize {
+        if let Some((mut result, phase)) = running {
+            let e = if timed_out {
+                format!("TIMEOUT (after {:.2?})", timeout.unwrap())
+            } else {
+                format!("child process failed: {status}")
+            };
+            fail_phase(&mut result, phase, last_record.elapsed(), e);
+            results.push(result);
+        } else if results.is_empty() {
+            results.push(DayResult {
+                day,
+                gen_elapsed: Duration::ZERO,
+                gen_error: Some(GenError::Failed(format!("child process failed: {status}"))),
+                part_1: None,
+                part_2: None,
+            });
+        }
+    }
+    Ok(results)
+}
+
+/// If this process was started by [`run_isolated`], run the requested day (as many times as
+/// requested) and exit
+pub fn run_child_if_requested() {
+    let Ok(day) = env::var(CHILD_DAY_ENV) else {
+        return;
+    };
+    let day: u32 = day.parse().expect("invalid day number");
+    let year = env::var(CHILD_YEAR_ENV).expect("missing year");
+    let year: u32 = year.parse().expect("invalid year");
+    let runner = crate::day(year, day).expect("day not implemented");
+    let parts = env::var(CHILD_PARTS_ENV).unwrap_or_default();
+    let parts = Parts::from_str(&parts, false).expect("invalid parts");
+    let runs = env::var(CHILD_RUNS_ENV).map_or(1, |runs| runs.parse().expect("invalid runs"));
+    let mut input = String::new();
+    io::stdin().read_to_string(&mut input).unwrap();
+
+    IS_CHILD.store(true, Ordering::Relaxed);
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        let checkpoint = CHECKPOINT.with(|c| c.borrow_mut().take());
+        let elapsed = checkpoint.as_ref().map(|(_, _, start)| start.elapsed());
+        default_hook(info);
+        // If panics unwind, the runner catches it, and reports as usual
+        if !PANICS_ABORT {
+            return;
+        }
+        let (Some((mut result, phase, _)), Some(elapsed)) = (checkpoint, elapsed) else {
+            return;
+        };
+        let e = format!("panic'd: {}", panic_message(info.payload()));
+        fail_phase(&mut result, phase, elapsed, e);
+        print!("{}", encode(&result, None));
+        let _ = io::stdout().flush();
+    }));
+
+    for _ in 0..runs {
+        let result = runner(&input, parts);
+        print!("{}", encode(&result, None));
+        let _ = io::stdout().flush();
+    }
+    process::exit(0);
+}
+
+fn fail_phase(result: &mut DayResult, phase: Phase, elapsed: Duration, e: String) {
+    let failed_part = |e| {
+        Some(PartResult {
+            elapsed,
+            answer: Err(e),
+        })
+    };
+    match phase {
+        Phase::Generator => {
+            result.gen_elapsed = elapsed;
+            result.gen_error = Some(GenError::Failed(e));
+        }
+        Phase::Part1 => result.part_1 = failed_part(e),
+        Phase::Part2 => result.part_2 = failed_part(e),
+    }
+}
+
+// A line based encoding, with one field per line, ending with `end`.
+//
+// Partial results also include the phase which is still running. A child running a day several
+// times reports each run in turn. Every line starts with
+// `RECORD_MARKER`, so it can be told apart from the day's own output, even if the day left a
+// partial line for it to be appended to.
+fn encode(result: &DayResult, running: Option<Phase>) -> String {
+    let mut records = vec![
+        format!("day {}", result.day),
+        format!("gen {}", result.gen_elapsed.as_nanos()),
+    ];
+    match &result.gen_error {
+        Some(GenError::Parse(e)) => records.push(format!(
+            "parse_error {} {} {}",
+            e.line,
+            e.column,
+            escape(&e.expected)
+        )),
+        Some(GenError::Failed(e)) => records.push(format!("gen_error {}", escape(e))),
+        None => {}
+    }
+    for (name, part) in [("part_1", &result.part_1), ("part_2", &result.part_2)] {
+        if let Some(part) = part {
+            let (status, answer) = match &part.answer {
+                Ok(answer) => ("ok", answer),
+                Err(e) => ("err", e),
+            };
+            records.push(format!(
+                "{name} {} {status} {}",
+                part.elapsed.as_nanos(),
+                escape(answer)
+            ));
+        }
+    }
+    if let Some(phase) = running {
+        records.push(format!("running {}", phase.name()));
+    }
+    records.push("end".to_string());
+    records
+        .iter()
+        .map(|record| format!("{RECORD_MARKER}{record}\n"))
+        .collect()
+}
+
+/// The record on a line of the child's output, if any
+fn record(line: &str) -> Option<&str> {
+    Some(line.split_once(RECORD_MARKER)?.1)
+}
+
+// Decode each complete result in `s`, in order, with the phase which was still running, if any
+//
+// Anything the day printed itself is skipped, as are malformed records, and records which aren't
+// part of a result.
+fn decode(s: &str) -> Vec<(DayResult, Option<Phase>)> {
+    let mut complete = Vec::new();
+    let mut result = None;
+    let mut running = None;
+    for record in s.lines().filter_map(record) {
+        let (key, value) = record.split_once(' ').unwrap_or((record, ""));
+        match (key, &mut result) {
+            ("day", _) => {
+                running = None;
+                result = value.parse().ok().map(|day| DayResult {
+                    day,
+                    gen_elapsed: Duration::ZERO,
+                    gen_error: None,
+                    part_1: None,
+                    part_2: None,
+                });
+            }
+            ("end", Some(_)) => complete.push((result.take().unwrap(), running)),
+            ("running", Some(_)) => running = Phase::from_name(value).or(running),
+            (_, Some(result)) => {
+                let _ = decode_field(result, key, value);
+            }
+            (_, None) => {}
+        }
+    }
+    complete
+}
+
+/// Decode a field of `result`, `None` if it's malformed
+fn decode_field(result: &mut DayResult, key: &str, value: &str) -> Option<()> {
+    match key {
+        "gen" => result.gen_elapsed = parse_nanos(value)?,
+        "gen_error" => result.gen_error = Some(GenError::Failed(unescape(value))),
+        "parse_error" => {
+            let mut fields = value.splitn(3, ' ');
+            result.gen_error = Some(GenError::Parse(ParseError {
+                day: result.day,
+                line: fields.next()?.parse().ok()?,
+                column: fields.next()?.parse().ok()?,
+                expected: unescape(fields.next()?),
+            }));
+        }
+        "part_1" | "part_2" => {
+            let mut fields = value.splitn(3, ' ');
+            let elapsed = parse_nanos(fields.next()?)?;
+            let answer = match (fields.next()?, unescape(fields.next()?)) {
+                ("ok", answer) => Ok(answer),
+                (_, e) => Err(e),
+            };
+            let part = Some(PartResult { elapsed, answer });
+            if key == "part_1" {
+                result.part_1 = part;
+            } else {
+                result.part_2 = part;
+            }
+        }
+        _ => return None,
+    }
+    Some(())
+}
+
+fn parse_nanos(s: &str) -> Option<Duration> {
+    Some(Duration::from_nanos(s.parse().ok()?))
+}
+
+pub(crate) fn escape(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+}
+
+pub(crate) fn unescape(s: &str) -> String {
+    let mut result = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            result.push(ch);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => result.push('\n'),
+            Some('r') => result.push('\r'),
+            Some(ch) => result.push(ch),
+            None => {}
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_decode() {
+        let result = DayResult {
+            day: 10,
+            gen_elapsed: Duration::from_nanos(1234),
+            gen_error: None,
+            part_1: Some(PartResult {
+                elapsed: Duration::from_nanos(5),
+                answer: Ok("\n#..\\n#".to_string()),
+            }),
+            part_2: Some(PartResult {
+                elapsed: Duration::from_nanos(6),
+                answer: Err("panic'd: oh no".to_string()),
+            }),
+        };
+        let (decoded, running) = decode(&format!("noise\n{}", encode(&result, None)))
+            .pop()
+            .unwrap();
+        assert_eq!(format!("{decoded:?}"), format!("{result:?}"));
+        assert_eq!(running, None);
+
+        // A child which died part way through a phase
+        let mut partial = result.clone();
+        partial.part_2 = None;
+        let started = format!("{RECORD_MARKER}day 10\n{RECORD_MARKER}gen 5\n");
+        let output = encode(&partial, Some(Phase::Part2)) + &started;
+        let (decoded, running) = decode(&output).pop().unwrap();
+        assert_eq!(format!("{decoded:?}"), format!("{partial:?}"));
+        assert_eq!(running, Some(Phase::Part2));
+
+        // A child which died before reporting anything
+        assert!(decode(&started).is_empty());
+
+        // A day which printed a partial line, and a malformed record
+        let end = format!("{RECORD_MARKER}end");
+        let output = format!("50% done... {}", encode(&result, None))
+            .replace(&end, &format!("{RECORD_MARKER}part_2 oops\n{end}"));
+        let (decoded, running) = decode(&output).pop().unwrap();
+        assert_eq!(format!("{decoded:?}"), format!("{result:?}"));
+        assert_eq!(running, None);
+        // Records which aren't part of a result are skipped
+        assert!(decode(&format!("{RECORD_MARKER}gen 5\n{RECORD_MARKER}end\n")).is_empty());
+
+        // A child running the day several times, reporting its progress on the last run
+        let output = encode(&result, None) + &encode(&partial, Some(Phase::Part2));
+        let output = output.repeat(2);
+        let decoded = decode(&output);
+        let running: Vec<_> = decoded.iter().map(|(_, running)| *running).collect();
+        assert_eq!(
+            running,
+            [None, Some(Phase::Part2), None, Some(Phase::Part2)]
+        );
+
+        // A generator which rejected its input
+        let rejected = DayResult {
+            gen_error: Some(GenError::Parse(ParseError {
+                day: 10,
+                line: 3,
+                column: 41,
+                expected: "\"ore and \"".to_string(),
+            })),
+            part_1: None,
+            part_2: None,
+            ..result
+        };
+        let (decoded, _) = decode(&encode(&rejected, None)).pop().unwrap();
+        assert_eq!(decoded.gen_error, rejected.gen_error);
+    }
+}
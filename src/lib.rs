@@ -1,64 +1,107 @@
 extern crate core;
 
+pub mod answers;
+pub mod config;
+pub mod crypt;
+pub mod geom;
 pub mod grid;
+pub mod input;
+pub mod isolate;
+pub mod parse;
+pub mod provider;
+pub mod report;
+pub mod scaffold;
+pub mod search;
+pub mod stats;
+pub mod submit;
 
-days![
-    day1, day2, day3, day4, day5, day6, day7, day8, day9, day10, day11, day12, day13, day14, day15,
-    day16, day17, day18, day19, day20, day23, day24, day25
-];
+/// The year of the days at the root of the crate, `src/dayN.rs`, the days of other years are in
+/// `src/yYYYY/dayN.rs`
+pub const ROOT_YEAR: u32 = 2022;
 
-#[allow(dead_code)]
-fn unimplemented_part<I>(_input: &I) -> &'static str {
-    "Unimplemented"
-}
+// The `days!` and `years!` registry of every `src/dayN.rs` and `src/yYYYY/dayN.rs`
+include!(concat!(env!("OUT_DIR"), "/days.rs"));
 
+/// Tests of a day's solution against the answers in `input/<year>/answers.toml`
+///
+/// The demo input is embedded in the test, and the real input read relative to the crate, so
+/// tests run from any directory. Tests of a missing input, or of an encrypted one without a key,
+/// are ignored, see `build.rs`.
 macro_rules! day_test {
-    (demo_1 == $result:expr) => {
-        #[test]
-        fn test_demo_1() {
-            let input = $crate::day_test!(@demo_input);
-            let input = generator(&input);
-            assert_eq!(part_1(&input), $result);
+    (demo_1) => {
+        day_input! {
+            demo_test
+            #[test]
+            fn test_demo_1() {
+                let input = $crate::day_test!(@demo_input);
+                let input = <Day as $crate::Solution>::parse(&input).unwrap();
+                assert_eq!(
+                    <Day as $crate::Solution>::part_1(&input).to_string(),
+                    $crate::day_test!(@answer demo_1)
+                );
+            }
         }
     };
-    (demo_2 == $result:expr) => {
-        #[test]
-        fn test_demo_2() {
-            let input = $crate::day_test!(@demo_input);
-            let input = generator(&input);
-            assert_eq!(part_2(&input), $result);
+    (demo_2) => {
+        day_input! {
+            demo_test
+            #[test]
+            fn test_demo_2() {
+                let input = $crate::day_test!(@demo_input);
+                let input = <Day as $crate::Solution>::parse(&input).unwrap();
+                assert_eq!(
+                    <Day as $crate::Solution>::part_2(&input).to_string(),
+                    $crate::day_test!(@answer demo_2)
+                );
+            }
         }
     };
-    (part_1 == $result:expr) => {
-        #[test]
-        fn test_part_1() {
-            let input = $crate::day_test!(@real_input);
-            let input = generator(&input);
-            assert_eq!(part_1(&input), $result);
+    (part_1) => {
+        day_input! {
+            real_test
+            #[test]
+            fn test_part_1() {
+                let input = $crate::day_test!(@real_input);
+                let input = <Day as $crate::Solution>::parse(&input).unwrap();
+                assert_eq!(
+                    <Day as $crate::Solution>::part_1(&input).to_string(),
+                    $crate::day_test!(@answer part_1)
+                );
+            }
         }
     };
-    (part_2 == $result:expr) => {
-        #[test]
-        fn test_part_2() {
-            let input = $crate::day_test!(@real_input);
-            let input = generator(&input);
-            assert_eq!(part_2(&input), $result);
+    (part_2) => {
+        day_input! {
+            real_test
+            #[test]
+            fn test_part_2() {
+                let input = $crate::day_test!(@real_input);
+                let input = <Day as $crate::Solution>::parse(&input).unwrap();
+                assert_eq!(
+                    <Day as $crate::Solution>::part_2(&input).to_string(),
+                    $crate::day_test!(@answer part_2)
+                );
+            }
         }
     };
+    (@answer $key:ident) => {
+        $crate::answers::expected_answer(
+            $crate::extract_year(module_path!()),
+            $crate::extract_day_number(module_path!()),
+            stringify!($key),
+        )
+    };
     (@demo_input) => {
-        {
-            let day_num = $crate::extract_day_number(module_path!());
-            std::fs::read_to_string(&format!("input/2022/demo{day_num}.txt")).unwrap()
-        }
+        $crate::input::normalize(day_input!(demo)).0
     };
     (@real_input) => {
-        {
-            let day_num = $crate::extract_day_number(module_path!());
-            std::fs::read_to_string(&format!("input/2022/day{day_num}.txt")).unwrap()
-        }
+        $crate::input::read_input(std::path::Path::new(day_input!(real))).unwrap()
     };
 }
 use day_test;
+use parse::ParseError;
+use std::any::Any;
+use std::fmt;
 use std::fmt::Display;
 use std::panic;
 use std::time::{Duration, Instant};
@@ -87,6 +130,16 @@ const fn extract_day_number(s: &str) -> u32 {
     day_number
 }
 
+/// The year of a day module, from a `yYYYY` module in its path (e.g. `aoc_2022::y2023::day1`),
+/// days at the root of the crate are from 2022
+#[cfg(test)]
+fn extract_year(module_path: &str) -> u32 {
+    module_path
+        .split("::")
+        .find_map(|m| m.strip_prefix('y')?.parse().ok())
+        .unwrap_or(ROOT_YEAR)
+}
+
 fn time<T, F: FnOnce() -> T + panic::UnwindSafe>(f: F) -> (Duration, Result<T, String>) {
     let start = Instant::now();
     let result = run_catch_panic(f);
@@ -99,53 +152,237 @@ where
     F: FnOnce() -> T,
     F: panic::UnwindSafe,
 {
-    panic::catch_unwind(f).map_err(|e| {
-        let s: &str = e
-            .downcast_ref::<&str>()
-            .copied()
-            .or_else(|| e.downcast_ref::<String>().map(|s| &**s))
-            .unwrap_or("Unknown");
-        format!("panic'd: {}", s)
-    })
+    panic::catch_unwind(f).map_err(|e| format!("panic'd: {}", panic_message(&*e)))
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(|s| &**s))
+        .unwrap_or("Unknown")
+}
+
+fn stringify_res<T: Display>(r: Result<T, String>) -> Result<String, String> {
+    r.map(|t| t.to_string())
 }
 
-fn stringify_res<T: Display, E: Display>(r: Result<T, E>) -> String {
-    match r {
-        Ok(t) => t.to_string(),
-        Err(e) => e.to_string(),
+/// The outcome of running a single part of a day
+#[derive(Debug, Clone)]
+pub struct PartResult {
+    pub elapsed: Duration,
+    /// The answer, or a description of why the part failed
+    pub answer: Result<String, String>,
+}
+
+/// Why a day's generator failed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GenError {
+    /// The input was malformed
+    Parse(ParseError),
+    /// The generator panicked, or was stopped
+    Failed(String),
+}
+
+impl fmt::Display for GenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Parse(e) => e.fmt(f),
+            Self::Failed(e) => f.write_str(e),
+        }
     }
 }
 
-macro_rules! days {
-    ($($mod_name:ident),*) => {
-        $(pub mod $mod_name;)*
+/// The outcome of running a day's generator and both parts on an input
+#[derive(Debug, Clone)]
+pub struct DayResult {
+    pub day: u32,
+    pub gen_elapsed: Duration,
+    /// If the generator failed, neither part is run
+    pub gen_error: Option<GenError>,
+    pub part_1: Option<PartResult>,
+    pub part_2: Option<PartResult>,
+}
+
+impl DayResult {
+    pub fn total_elapsed(&self) -> Duration {
+        let part_elapsed =
+            |part: &Option<PartResult>| part.as_ref().map_or(Duration::ZERO, |p| p.elapsed);
+        self.gen_elapsed + part_elapsed(&self.part_1) + part_elapsed(&self.part_2)
+    }
+}
+
+impl fmt::Display for DayResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(e) = &self.gen_error {
+            return writeln!(f, "Generator error: {e}");
+        }
+        writeln!(f, "Gen    ({:.2?})", self.gen_elapsed)?;
+        for (i, part) in [&self.part_1, &self.part_2].into_iter().enumerate() {
+            if let Some(part) = part {
+                let answer = match &part.answer {
+                    Ok(answer) | Err(answer) => answer,
+                };
+                writeln!(f, "Part {} ({:.2?}) {answer}", i + 1, part.elapsed)?;
+            }
+        }
+        writeln!(f, "Total  ({:.2?})", self.total_elapsed())
+    }
+}
+
+pub type DayRunner = fn(&str, Parts) -> DayResult;
+
+/// Which parts of a day to run, the generator is always run
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum Parts {
+    #[value(name = "1")]
+    Part1,
+    #[value(name = "2")]
+    Part2,
+    #[default]
+    Both,
+}
+
+impl Parts {
+    pub fn part_1(self) -> bool {
+        matches!(self, Self::Part1 | Self::Both)
+    }
+
+    pub fn part_2(self) -> bool {
+        matches!(self, Self::Part2 | Self::Both)
+    }
+}
+
+/// A solution to a single day's puzzle
+///
+/// The input is parsed once by [`Solution::parse`], then both parts are run on the parsed input.
+pub trait Solution {
+    /// The parsed input, which may borrow from the input string
+    type Input<'a>;
+    type Answer1: Display;
+    type Answer2: Display;
+
+    /// False for days with only one part (day 25), [`Solution::part_2`] is never run for them
+    const HAS_PART_2: bool = true;
+
+    fn parse(s: &str) -> Result<Self::Input<'_>, ParseError>;
+    fn part_1(input: &Self::Input<'_>) -> Self::Answer1;
+    fn part_2(input: &Self::Input<'_>) -> Self::Answer2;
+}
+
+/// The answer type of a part which doesn't exist, see [`Solution::HAS_PART_2`]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum NoAnswer {}
+
+impl Display for NoAnswer {
+    fn fmt(&self, _f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {}
+    }
+}
+
+/// Run the generator and the selected parts of `S` on `s`, timing each, and catching any panics
+pub fn run_solution<S: Solution>(day: u32, s: &str, parts: Parts) -> DayResult {
+    use isolate::{checkpoint, Phase};
+
+    let mut result = DayResult {
+        day,
+        gen_elapsed: Duration::ZERO,
+        gen_error: None,
+        part_1: None,
+        part_2: None,
+    };
+
+    checkpoint(&result, Phase::Generator);
+    let (gen_elapsed, input) = time(|| S::parse(s));
+    result.gen_elapsed = gen_elapsed;
+    let input = match input {
+        Ok(Ok(i)) => i,
+        Ok(Err(e)) => {
+            result.gen_error = Some(GenError::Parse(ParseError { day, ..e }));
+            return result;
+        }
+        Err(e) => {
+            result.gen_error = Some(GenError::Failed(e));
+            return result;
+        }
+    };
+    // If part 1 panics, part 2 still gets a go on the same input
+    let input = panic::AssertUnwindSafe(&input);
 
-        pub const DAYS: [Option<fn(&str, bool)>; 25] = {
-            let mut result: [Option<fn(&str, bool)>; 25] = [None; 25];
+    if parts.part_1() {
+        checkpoint(&result, Phase::Part1);
+        let (elapsed, answer) = time(|| S::part_1(*input));
+        result.part_1 = Some(PartResult {
+            elapsed,
+            answer: stringify_res(answer),
+        });
+    }
+
+    if parts.part_2() && S::HAS_PART_2 {
+        checkpoint(&result, Phase::Part2);
+        let (elapsed, answer) = time(|| S::part_2(*input));
+        result.part_2 = Some(PartResult {
+            elapsed,
+            answer: stringify_res(answer),
+        });
+    }
+
+    result
+}
+
+/// A day's generator, returning a handle to run each part on the generated input
+///
+/// Unlike [`DayRunner`], this allows each phase to be run (and benchmarked) separately.
+pub type DayGenerator = for<'a> fn(&'a str) -> Result<Box<dyn Generated + 'a>, ParseError>;
+
+/// The output of a day's generator, which both parts can be run on
+pub trait Generated {
+    /// Run part 1, passing the answer through [`std::hint::black_box`] rather than returning it
+    fn part_1(&self);
+    /// Run part 2, passing the answer through [`std::hint::black_box`] rather than returning it
+    fn part_2(&self);
+    fn has_part_2(&self) -> bool;
+}
+
+struct GeneratedInput<'a, S: Solution> {
+    input: S::Input<'a>,
+}
+
+impl<S: Solution> Generated for GeneratedInput<'_, S> {
+    fn part_1(&self) {
+        std::hint::black_box(S::part_1(&self.input));
+    }
+
+    fn part_2(&self) {
+        std::hint::black_box(S::part_2(&self.input));
+    }
+
+    fn has_part_2(&self) -> bool {
+        S::HAS_PART_2
+    }
+}
+
+/// Run the generator of `S`, for use as a [`DayGenerator`]
+pub fn generate<S: Solution + 'static>(s: &str) -> Result<Box<dyn Generated + '_>, ParseError> {
+    Ok(Box::new(GeneratedInput::<S> {
+        input: S::parse(s)?,
+    }))
+}
+
+/// Register the day modules `$mod_name`, declared by `build.rs`
+macro_rules! days {
+    ($($mod_name:ident),* $(,)?) => {
+        pub const DAYS: [Option<$crate::DayRunner>; 25] = {
+            let mut result: [Option<$crate::DayRunner>; 25] = [None; 25];
 
             $(
             {
-                fn run_day(s: &str, print: bool) {
-                    let (gen_elapsed, input) = $crate::time(|| $mod_name::generator(s));
-                    let input = match input {
-                        Ok(i) => i,
-                        Err(e) => {
-                            println!("Generator error: {e}");
-                            return;
-                        }
-                    };
-                    let (p1_elapsed, p1_result) = $crate::time(|| $mod_name::part_1(&input));
-                    let (p2_elapsed, p2_result) = $crate::time(|| $mod_name::part_2(&input));
-
-                    let p1_result = $crate::stringify_res(p1_result);
-                    let p2_result = $crate::stringify_res(p2_result);
-
-                    if print {
-                        println!("Gen    ({:.2?})", gen_elapsed);
-                        println!("Part 1 ({:.2?}) {p1_result}", p1_elapsed);
-                        println!("Part 2 ({:.2?}) {p2_result}", p2_elapsed);
-                        println!("Total  ({:.2?})", gen_elapsed + p1_elapsed + p2_elapsed);
-                    }
+                fn run_day(s: &str, parts: $crate::Parts) -> $crate::DayResult {
+                    $crate::run_solution::<$mod_name::Day>(
+                        $crate::extract_day_number(stringify!($mod_name)),
+                        s,
+                        parts,
+                    )
                 }
 
                 result[$crate::extract_day_number(stringify!($mod_name)) as usize - 1] = Some(run_day);
@@ -154,6 +391,96 @@ macro_rules! days {
 
             result
         };
+
+        pub const GENERATORS: [Option<$crate::DayGenerator>; 25] = {
+            let mut result: [Option<$crate::DayGenerator>; 25] = [None; 25];
+
+            $(
+            result[$crate::extract_day_number(stringify!($mod_name)) as usize - 1] =
+                Some($crate::generate::<$mod_name::Day>);
+            )*
+
+            result
+        };
     };
 }
 use days;
+
+/// The days implemented for one year, registered by [`years!`]
+#[derive(Copy, Clone)]
+pub struct Year {
+    pub year: u32,
+    pub days: [Option<DayRunner>; 25],
+    pub generators: [Option<DayGenerator>; 25],
+}
+
+impl Year {
+    pub fn day(&self, day: u32) -> Option<DayRunner> {
+        let i = usize::try_from(day).ok()?.checked_sub(1)?;
+        *self.days.get(i)?
+    }
+
+    pub fn generator(&self, day: u32) -> Option<DayGenerator> {
+        let i = usize::try_from(day).ok()?.checked_sub(1)?;
+        *self.generators.get(i)?
+    }
+
+    /// The implemented days, in order, with their runners
+    pub fn implemented_days(&self) -> impl Iterator<Item = (u32, DayRunner)> + '_ {
+        (1..)
+            .zip(self.days)
+            .filter_map(|(day, runner)| Some((day, runner?)))
+    }
+
+    pub fn latest_day(&self) -> Option<u32> {
+        self.implemented_days().last().map(|(day, _)| day)
+    }
+}
+
+/// The registered year `year`, if any
+pub fn year(year: u32) -> Option<&'static Year> {
+    YEARS.iter().find(|y| y.year == year)
+}
+
+/// The most recent registered year
+pub fn latest_year() -> &'static Year {
+    YEARS.last().expect("at least one year is registered")
+}
+
+/// The runner for `day` of `year`, if it's implemented
+pub fn day(year: u32, day: u32) -> Option<DayRunner> {
+    self::year(year)?.day(day)
+}
+
+/// Register each year's days, given the module `days!` was used in, oldest year first
+macro_rules! years {
+    ($($year:literal => $module:ident),* $(,)?) => {
+        pub const YEARS: &[$crate::Year] = &[$(
+            $crate::Year {
+                year: $year,
+                days: $module::DAYS,
+                generators: $module::GENERATORS,
+            },
+        )*];
+    };
+}
+use years;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn year_registry() {
+        assert_eq!(extract_year("aoc_2022::day19"), 2022);
+        assert_eq!(extract_year("aoc_2022::y2023::day1::tests"), 2023);
+
+        let year = year(2022).unwrap();
+        assert_eq!(year.latest_day(), Some(25));
+        assert_eq!(year.implemented_days().count(), 25);
+        assert!(day(2022, 1).is_some());
+        assert!(day(2022, 0).is_none());
+        assert!(day(2022, 26).is_none());
+        assert!(day(1999, 1).is_none());
+    }
+}
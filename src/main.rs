@@ -1,76 +1,485 @@
-use aoc_2022::DAYS;
+use aoc_2022::answers::Answers;
+use aoc_2022::config::Config;
+use aoc_2022::crypt::{self, Key};
+use aoc_2022::provider::{self, InputProvider};
+use aoc_2022::report::{self, Format};
+use aoc_2022::stats::RepeatedResult;
+use aoc_2022::submit::{Guesses, Outcome, Submitter};
+use aoc_2022::{input, isolate, scaffold};
+use aoc_2022::{DayResult, DayRunner, GenError, Parts, Year};
 use clap::Parser;
-use std::path::PathBuf;
-use std::time::Instant;
-use std::{fs, mem};
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use std::{io, mem};
 
 #[derive(clap::Parser, Debug)]
-#[command(author, version, about, long_about = None)]
+#[command(author, version, about, long_about = None, args_conflicts_with_subcommands = true)]
 struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     /// Day to run
     ///
     /// If not passed, all days are run in order
-    day: Option<usize>,
+    day: Option<u32>,
 
-    /// Path to load input from (defaults to path in input/2022 based on day name)
+    /// Path to load input from, or `-` for stdin (defaults to path in input/<year> based on day
+    /// name, which is fetched if it's missing and AOC_SESSION or ~/.adventofcode.session is set)
     input: Option<PathBuf>,
 
+    /// Year to run days from (defaults to the latest year)
+    #[arg(short, long, global = true)]
+    year: Option<u32>,
+
     /// Try to load demo input
     #[arg(short, long)]
     demo: bool,
 
     #[arg(short, long, conflicts_with = "day")]
     latest: bool,
+
+    /// Output results in a machine readable format, instead of as they're run
+    #[arg(short, long)]
+    format: Option<Format>,
+
+    /// Compare answers to the expected answers in input/<year>/answers.toml
+    ///
+    /// Exits with an error if any answer doesn't match
+    #[arg(short, long, conflicts_with = "format")]
+    check: bool,
+
+    /// Stop any day which takes longer than this, e.g. `30s`, `500ms`, or `2m`
+    #[arg(short, long, value_parser = parse_duration)]
+    timeout: Option<Duration>,
+
+    /// List the implemented days of each year, and any which are missing their input
+    #[arg(long, conflicts_with_all = ["day", "latest"])]
+    list: bool,
+
+    /// Which parts to run
+    #[arg(short, long, default_value = "both")]
+    part: Parts,
+
+    /// Run each day this many times, and report the min, median, mean and standard deviation
+    /// of each phase
+    #[arg(
+        short,
+        long,
+        default_value_t = 1,
+        value_parser = clap::value_parser!(u32).range(1..),
+        conflicts_with_all = ["format", "check"],
+    )]
+    repeat: u32,
+
+    /// Run each day this many times before the timed runs, without reporting the timings
+    #[arg(short, long, default_value_t = 0, conflicts_with_all = ["format", "check"])]
+    warmup: u32,
+}
+
+#[derive(clap::Subcommand, Debug)]
+enum Command {
+    /// Create the module for a new day, with empty demo and real inputs
+    New {
+        /// Day to create
+        day: u32,
+    },
+    /// Run a part of a day on the real input, and submit the answer
+    ///
+    /// Rejected answers are saved in input/<year>/guesses.txt, and aren't submitted again.
+    Submit {
+        day: u32,
+        #[arg(value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: u8,
+    },
+    /// Encrypt real inputs to dayN.txt.enc, removing the plaintext
+    ///
+    /// Creates a new key in .input_key if there's no key yet.
+    Encrypt {
+        /// Day to encrypt the input of, all days with plaintext inputs if not passed
+        day: Option<u32>,
+    },
+    /// Decrypt real inputs from dayN.txt.enc, keeping the encrypted files
+    Decrypt {
+        /// Day to decrypt the input of, all days with encrypted inputs if not passed
+        day: Option<u32>,
+    },
+}
+
+impl Args {
+    fn repeated(&self) -> bool {
+        self.repeat > 1 || self.warmup > 0
+    }
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let args = Args::parse();
-    let single_day = args.day.or_else(|| {
-        if args.latest {
-            Some(latest_day())
-        } else {
-            None
+    isolate::run_child_if_requested();
+    let mut args = Args::parse();
+    let command_year = args.year.unwrap_or(aoc_2022::latest_year().year);
+    match args.command {
+        Some(Command::New { day }) => {
+            let created =
+                scaffold::new_day(Path::new("."), command_year, day).map_err(|e| e.to_string())?;
+            for path in created {
+                println!("Created {}", path.display());
+            }
+            return Ok(());
         }
-    });
+        Some(Command::Encrypt { day }) => return encrypt_inputs(command_year, day),
+        Some(Command::Decrypt { day }) => return decrypt_inputs(command_year, day),
+        Some(Command::Submit { .. }) | None => {}
+    }
+    if args.list {
+        list_days();
+        return Ok(());
+    }
+    let year = match args.year {
+        Some(year) => aoc_2022::year(year).ok_or_else(|| format!("Year {year} not implemented"))?,
+        None => aoc_2022::latest_year(),
+    };
+    let single_day = args
+        .day
+        .or_else(|| if args.latest { year.latest_day() } else { None });
+    let answers = if args.check {
+        Some(Answers::load_year(year.year)?)
+    } else {
+        None
+    };
+    let mut failures = 0;
+    let provider = provider::default_provider(Path::new("."));
+    if let Some(Command::Submit { day, part }) = args.command {
+        return submit(&*provider, year, day, part);
+    }
+
     if let Some(day) = single_day {
-        println!("Day {day}");
-        let input_path = args.input.unwrap_or_else(|| input_for_day(day, args.demo));
-        let input = fs::read_to_string(input_path)?;
+        if args.format.is_none() && !args.check {
+            println!("Day {day}");
+        }
+        let explicit_path = args.input.take();
+        let input = read_day_input(&*provider, explicit_path.as_deref(), year, day, args.demo)
+            .map_err(|e| e.to_string())?;
+        let input_path =
+            explicit_path.unwrap_or_else(|| input::day_path(year.year, day, args.demo));
 
-        let runner = DAYS[day - 1].ok_or_else(|| format!("Day {day} not implemented"))?;
+        let runner = year
+            .day(day)
+            .ok_or_else(|| format!("Day {day} of {} not implemented", year.year))?;
 
-        runner(&input, true);
-        return Ok(());
+        if args.repeated() {
+            let repeated = run_repeated(&args, runner, year, day, &input)?;
+            print_result(&repeated, &repeated.last, &input_path);
+            return Ok(());
+        }
+        let result = isolate::run_day(runner, year.year, day, &input, args.part, args.timeout)?;
+        if let Some(answers) = &answers {
+            failures += print_check(answers, &result, args.demo, &input_path);
+        } else if let Some(format) = args.format {
+            let total = result.total_elapsed();
+            report::write_report(&mut io::stdout().lock(), format, &[result], total)?;
+        } else {
+            print_result(&result, &result, &input_path);
+        }
+        return check_failures(failures);
     }
 
     let overall_start = Instant::now();
     let mut first = true;
-    for (i, runner) in DAYS.iter().enumerate() {
-        let Some(runner) = runner else { continue; };
-        let day = i + 1;
+    let mut results = Vec::with_capacity(year.days.len());
+    // The sum of each day's median total time, when runs are repeated
+    let mut total_median = Duration::ZERO;
+    for (day, runner) in year.implemented_days() {
+        let input_path = input::day_path(year.year, day, args.demo);
+        let input = match read_day_input(&*provider, None, year, day, args.demo) {
+            Ok(input) => input,
+            Err(e) => {
+                eprintln!(
+                    "Skipping day {day}, unable to read {}: {e}",
+                    input_path.display()
+                );
+                continue;
+            }
+        };
 
-        if mem::replace(&mut first, false) {
-            println!();
+        if args.repeated() {
+            let repeated = run_repeated(&args, runner, year, day, &input)?;
+            if mem::replace(&mut first, false) {
+                println!();
+            }
+            println!("Day {day}");
+            print_result(&repeated, &repeated.last, &input_path);
+            total_median += repeated.total.median;
+            continue;
         }
-        println!("Day {day}");
-        let input_path = input_for_day(day, args.demo);
-        let input = fs::read_to_string(input_path)?;
 
-        runner(&input, true);
+        let result = isolate::run_day(runner, year.year, day, &input, args.part, args.timeout)?;
+        if let Some(answers) = &answers {
+            failures += print_check(answers, &result, args.demo, &input_path);
+        } else if args.format.is_none() {
+            if mem::replace(&mut first, false) {
+                println!();
+            }
+            println!("Day {day}");
+            print_result(&result, &result, &input_path);
+        }
+        results.push(result);
     }
     let total_time = overall_start.elapsed();
-    println!();
-    println!("Total time: {:.2?}", total_time);
+    if answers.is_some() {
+        return check_failures(failures);
+    }
+    match args.format {
+        Some(format) => {
+            report::write_report(&mut io::stdout().lock(), format, &results, total_time)?;
+        }
+        None if args.repeated() => {
+            println!();
+            println!("Total time (sum of medians): {total_median:.2?}");
+        }
+        None => {
+            println!();
+            println!("Total time: {:.2?}", total_time);
+        }
+    }
     Ok(())
 }
 
-fn latest_day() -> usize {
-    DAYS.iter().rposition(|d| d.is_some()).unwrap() + 1
+// Run `part` of `day`, and submit the answer, unless it's known to be wrong
+fn submit(
+    provider: &dyn InputProvider,
+    year: &Year,
+    day: u32,
+    part: u8,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let submitter = Submitter::new(&Config::load()?).map_err(|e| e.to_string())?;
+    let runner = year
+        .day(day)
+        .ok_or_else(|| format!("Day {day} of {} not implemented", year.year))?;
+    let input = provider.input(year.year, day).map_err(|e| e.to_string())?;
+    let parts = if part == 1 {
+        Parts::Part1
+    } else {
+        Parts::Part2
+    };
+    let result = isolate::run_day(runner, year.year, day, &input, parts, None)?;
+    if let Some(e) = result.gen_error {
+        return Err(format!("Day {day}: generator error: {e}").into());
+    }
+    let part_result = if part == 1 {
+        result.part_1
+    } else {
+        result.part_2
+    };
+    let answer = part_result
+        .ok_or_else(|| format!("Day {day} has no part {part}"))?
+        .answer
+        .map_err(|e| format!("Day {day} part {part}: {e}"))?;
+
+    let mut guesses = Guesses::load(input::year_dir(year.year).join("guesses.txt"))?;
+    if let Some(reason) = guesses.known_wrong(day, part, &answer) {
+        return Err(format!("Not submitting {answer:?}, {reason}").into());
+    }
+    println!("Submitting {answer:?} for day {day} part {part}");
+    let outcome = submitter
+        .submit(year.year, day, part, &answer)
+        .map_err(|e| e.to_string())?;
+    println!("{outcome}");
+    guesses.record(day, part, &outcome, &answer)?;
+    match outcome {
+        Outcome::Right => Ok(()),
+        _ => Err("The answer wasn't accepted".into()),
+    }
 }
 
-fn input_for_day(day: usize, demo: bool) -> PathBuf {
-    let prefix = if demo { "demo" } else { "day" };
-    PathBuf::from(format!("input/2022/{prefix}{day}.txt"))
+// Encrypt the real input of `day`, or of every day with a plaintext input
+fn encrypt_inputs(year: u32, day: Option<u32>) -> Result<(), Box<dyn std::error::Error>> {
+    let key = match Key::load()? {
+        Some(key) => key,
+        None => {
+            let key = Key::generate();
+            let path = key.save_new()?;
+            println!("Created a new key in {}, keep it safe", path.display());
+            key
+        }
+    };
+    let paths = input_paths(year, day, input::is_filled);
+    if paths.is_empty() {
+        return Err(format!(
+            "No plaintext inputs to encrypt in {}",
+            input::year_dir(year).display()
+        )
+        .into());
+    }
+    for path in paths {
+        let encrypted_path =
+            crypt::encrypt_file(&key, &path).map_err(|e| format!("{}: {e}", path.display()))?;
+        println!("Encrypted {}", encrypted_path.display());
+    }
+    Ok(())
+}
+
+// Decrypt the real input of `day`, or of every day with an encrypted input
+fn decrypt_inputs(year: u32, day: Option<u32>) -> Result<(), Box<dyn std::error::Error>> {
+    let key = Key::require().map_err(|e| e.to_string())?;
+    let paths = input_paths(year, day, |path| crypt::encrypted_path(path).exists());
+    if paths.is_empty() {
+        return Err(format!(
+            "No encrypted inputs to decrypt in {}",
+            input::year_dir(year).display()
+        )
+        .into());
+    }
+    for path in paths {
+        crypt::decrypt_file(&key, &path).map_err(|e| format!("{}: {e}", path.display()))?;
+        println!("Decrypted {}", path.display());
+    }
+    Ok(())
+}
+
+// The path of the real input for `day`, or for every day for which `include` is true
+fn input_paths(year: u32, day: Option<u32>, include: impl Fn(&Path) -> bool) -> Vec<PathBuf> {
+    match day {
+        Some(day) => vec![input::day_path(year, day, false)],
+        None => (1..=25)
+            .map(|day| input::day_path(year, day, false))
+            .filter(|path| include(path))
+            .collect(),
+    }
+}
+
+// Read the input at `path` if there is one, otherwise the demo input, or the real input from
+// `provider`, which fetches it if it's missing and there's a session token
+fn read_day_input(
+    provider: &dyn InputProvider,
+    path: Option<&Path>,
+    year: &Year,
+    day: u32,
+    demo: bool,
+) -> io::Result<String> {
+    match path {
+        Some(path) => input::read_input(path),
+        None if demo => input::read_input(&input::day_path(year.year, day, true)),
+        None => provider.input(year.year, day),
+    }
+}
+
+// Run a day `args.warmup` times, ignoring the results, then `args.repeat` times, all in the same
+// process, so the warmups warm up the process the repeats are timed in
+fn run_repeated(
+    args: &Args,
+    runner: DayRunner,
+    year: &Year,
+    day: u32,
+    input: &str,
+) -> io::Result<RepeatedResult> {
+    let runs = args.warmup + args.repeat;
+    let mut results =
+        isolate::run_day_repeated(runner, year.year, day, input, args.part, args.timeout, runs)?;
+    // A failed run ends them early, and is kept to report it, even if it was a warmup
+    let results = results.split_off((args.warmup as usize).min(results.len() - 1));
+    Ok(RepeatedResult::new(results).expect("there's always at least 1 run"))
+}
+
+// Print a day's output, unless its input couldn't be parsed, then print where the problem is,
+// like `input/2022/day19.txt:3:41: expected "ore and "`
+fn print_result(output: &impl Display, result: &DayResult, input_path: &Path) {
+    match &result.gen_error {
+        Some(GenError::Parse(e)) => println!("{}:{e}", input::display_path(input_path)),
+        _ => print!("{output}"),
+    }
+}
+
+// Print PASS/FAIL/MISSING for each part, returning the number of failures
+fn print_check(answers: &Answers, result: &DayResult, demo: bool, input_path: &Path) -> usize {
+    let day = result.day;
+    match &result.gen_error {
+        Some(GenError::Parse(e)) => {
+            println!("Day {day}: FAIL ({}:{e})", input::display_path(input_path));
+            return 1;
+        }
+        Some(e) => {
+            println!("Day {day}: FAIL (generator error: {e})");
+            return 1;
+        }
+        None => {}
+    }
+    let mut failures = 0;
+    for (i, verdict) in answers.check(result, demo).iter().enumerate() {
+        println!("Day {day} part {}: {verdict}", i + 1);
+        if verdict.is_failure() {
+            failures += 1;
+        }
+    }
+    failures
+}
+
+fn check_failures(failures: usize) -> Result<(), Box<dyn std::error::Error>> {
+    if failures > 0 {
+        return Err(format!("{failures} answer(s) failed the check").into());
+    }
+    Ok(())
+}
+
+// Print each year's implemented days, like `2022: days 1-20, 23-25 (no input for 23)`
+fn list_days() {
+    for year in aoc_2022::YEARS {
+        let days: Vec<u32> = year.implemented_days().map(|(day, _)| day).collect();
+        let no_input: Vec<u32> = days
+            .iter()
+            .copied()
+            .filter(|&day| {
+                let path = input::day_path(year.year, day, false);
+                !input::is_filled(&path) && !crypt::encrypted_path(&path).exists()
+            })
+            .collect();
+        print!("{}: days {}", year.year, day_ranges(&days));
+        if !no_input.is_empty() {
+            print!(" (no input for {})", day_ranges(&no_input));
+        }
+        println!();
+    }
+}
+
+// Compress sorted days into ranges, e.g. `1-3, 5`
+fn day_ranges(days: &[u32]) -> String {
+    let mut ranges: Vec<(u32, u32)> = Vec::new();
+    for &day in days {
+        match ranges.last_mut() {
+            Some((_, end)) if *end + 1 == day => *end = day,
+            _ => ranges.push((day, day)),
+        }
+    }
+    let ranges: Vec<String> = ranges
+        .into_iter()
+        .map(|(start, end)| {
+            if start == end {
+                start.to_string()
+            } else {
+                format!("{start}-{end}")
+            }
+        })
+        .collect();
+    ranges.join(", ")
+}
+
+fn parse_duration(s: &str) -> Result<Duration, String> {
+    let unit_start = s
+        .find(|ch: char| !ch.is_ascii_digit() && ch != '.')
+        .unwrap_or(s.len());
+    let (value, unit) = s.split_at(unit_start);
+    let value: f64 = value
+        .parse()
+        .map_err(|_| format!("invalid duration {s:?}"))?;
+    let seconds = match unit {
+        "ms" => value / 1000.0,
+        "" | "s" => value,
+        "m" => value * 60.0,
+        _ => {
+            return Err(format!(
+                "unknown duration unit {unit:?}, expected ms, s, or m"
+            ))
+        }
+    };
+    Duration::try_from_secs_f64(seconds).map_err(|e| e.to_string())
 }
 
 #[test]
@@ -78,3 +487,20 @@ fn verify_args() {
     use clap::CommandFactory;
     Args::command().debug_assert()
 }
+
+#[test]
+fn durations() {
+    assert_eq!(parse_duration("30s"), Ok(Duration::from_secs(30)));
+    assert_eq!(parse_duration("1.5"), Ok(Duration::from_millis(1500)));
+    assert_eq!(parse_duration("250ms"), Ok(Duration::from_millis(250)));
+    assert_eq!(parse_duration("2m"), Ok(Duration::from_secs(120)));
+    assert!(parse_duration("2h").is_err());
+    assert!(parse_duration("s").is_err());
+}
+
+#[test]
+fn ranges_of_days() {
+    assert_eq!(day_ranges(&[1, 2, 3, 5, 7, 8]), "1-3, 5, 7-8");
+    assert_eq!(day_ranges(&[25]), "25");
+    assert_eq!(day_ranges(&[]), "");
+}
@@ -0,0 +1,130 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// Malformed input for a day, with where in the input it was found, and what was expected there
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// Filled in by [`crate::run_solution`], 0 when returned directly from a generator
+    pub day: u32,
+    /// 1 based
+    pub line: usize,
+    /// 1 based, in bytes
+    pub column: usize,
+    /// What was expected, e.g. `"ore and "` or `a number`
+    pub expected: String,
+}
+
+/// Shown as `line:column: expected ...`, to follow the path of the input
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}: expected {}",
+            self.line, self.column, self.expected
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Helpers for parsing a day's input, which report errors at their position in the input
+///
+/// Every `&str` passed to these must be a slice of the input the parser was created with.
+#[derive(Debug, Copy, Clone)]
+pub struct Parser<'a> {
+    input: &'a str,
+}
+
+impl<'a> Parser<'a> {
+    pub fn new(input: &'a str) -> Self {
+        Self { input }
+    }
+
+    /// An error at the start of `at`
+    pub fn error(&self, at: &str, expected: impl Into<String>) -> ParseError {
+        let input_start = self.input.as_ptr() as usize;
+        let offset = (at.as_ptr() as usize)
+            .checked_sub(input_start)
+            .filter(|&offset| offset <= self.input.len())
+            .unwrap_or(self.input.len());
+        debug_assert!(
+            offset + at.len() <= self.input.len(),
+            "error location isn't part of the input"
+        );
+
+        let before = &self.input.as_bytes()[..offset];
+        let line_start = before
+            .iter()
+            .rposition(|&b| b == b'\n')
+            .map_or(0, |i| i + 1);
+        ParseError {
+            day: 0,
+            line: before.iter().filter(|&&b| b == b'\n').count() + 1,
+            column: offset - line_start + 1,
+            expected: expected.into(),
+        }
+    }
+
+    /// An error just after `at`, e.g. for something missing from the end of a line
+    pub fn error_after(&self, at: &str, expected: impl Into<String>) -> ParseError {
+        self.error(&at[at.len()..], expected)
+    }
+
+    /// `s` without `prefix`, or an error if it doesn't start with it
+    pub fn strip_prefix<'s>(&self, s: &'s str, prefix: &str) -> Result<&'s str, ParseError> {
+        s.strip_prefix(prefix)
+            .ok_or_else(|| self.error(s, format!("{prefix:?}")))
+    }
+
+    /// The parts of `s` before and after the first `delimiter`, or an error if there isn't one
+    pub fn split_once<'s>(
+        &self,
+        s: &'s str,
+        delimiter: &str,
+    ) -> Result<(&'s str, &'s str), ParseError> {
+        s.split_once(delimiter)
+            .ok_or_else(|| self.error_after(s, format!("{delimiter:?}")))
+    }
+
+    /// The next item of `iter`, or an error just after `within` (usually the line or input being
+    /// split) if there are none left
+    pub fn next<'s>(
+        &self,
+        iter: &mut impl Iterator<Item = &'s str>,
+        within: &str,
+        expected: &str,
+    ) -> Result<&'s str, ParseError> {
+        iter.next()
+            .ok_or_else(|| self.error_after(within, expected))
+    }
+
+    /// Parse `s` as a number
+    pub fn number<T: FromStr>(&self, s: &str) -> Result<T, ParseError> {
+        s.parse().map_err(|_| self.error(s, "a number"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn error_positions() {
+        let input = "a: 1\nb: x\n";
+        let p = Parser::new(input);
+        let (_, line) = input.split_once('\n').unwrap();
+        let (_, value) = p.split_once(line, ": ").unwrap();
+        let err = p.number::<u32>(value).unwrap_err();
+        assert_eq!((err.line, err.column), (2, 4));
+        assert_eq!(err.to_string(), "2:4: expected a number");
+
+        let err = p.strip_prefix(line, "c").unwrap_err();
+        assert_eq!(err.to_string(), "2:1: expected \"c\"");
+
+        let err = p.split_once(&line[..4], ";").unwrap_err();
+        assert_eq!(err.to_string(), "2:5: expected \";\"");
+
+        let err = p.error_after(input, "another line");
+        assert_eq!((err.line, err.column), (3, 1));
+    }
+}
@@ -0,0 +1,291 @@
+use crate::config::Config;
+use crate::input;
+use std::cell::OnceCell;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use std::{fs, io, thread};
+
+/// Somewhere the real input for a day can come from
+pub trait InputProvider {
+    /// The [`input::normalize`]d input for `day` of `year`, failing with
+    /// [`io::ErrorKind::NotFound`] if this provider doesn't have it
+    fn input(&self, year: u32, day: u32) -> io::Result<String>;
+}
+
+/// Inputs already saved locally, at `<root>/input/<year>/dayN.txt`
+#[derive(Debug, Clone)]
+pub struct LocalCache {
+    root: PathBuf,
+}
+
+impl LocalCache {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    pub fn path(&self, year: u32, day: u32) -> PathBuf {
+        self.root.join(input::day_path(year, day, false))
+    }
+
+    /// Save `input` for `day` of `year`
+    pub fn store(&self, year: u32, day: u32, input: &str) -> io::Result<()> {
+        let path = self.path(year, day);
+        fs::create_dir_all(path.parent().unwrap())?;
+        fs::write(path, input)
+    }
+}
+
+impl InputProvider for LocalCache {
+    /// An empty input is missing, so it's fetched like one
+    fn input(&self, year: u32, day: u32) -> io::Result<String> {
+        let path = self.path(year, day);
+        if path.exists() && !input::is_filled(&path) {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("{} is empty", path.display()),
+            ));
+        }
+        input::read_input(&path)
+    }
+}
+
+/// Fetches inputs from [`Config::base_url`], saving them in a [`LocalCache`]
+///
+/// Requests are at least [`HttpFetcher::min_interval`] apart, waiting if needed. The time of the
+/// last request is saved next to the cached inputs, in `input/.last_request`, so this holds
+/// across separate runs too.
+pub struct HttpFetcher {
+    base_url: String,
+    session: String,
+    cache: LocalCache,
+    agent: ureq::Agent,
+    pub min_interval: Duration,
+}
+
+impl HttpFetcher {
+    /// The default time between requests
+    pub const MIN_INTERVAL: Duration = Duration::from_secs(5);
+
+    /// Fails if `config` has no session token
+    pub fn new(config: &Config, cache: LocalCache) -> io::Result<Self> {
+        Ok(Self {
+            base_url: config.base_url.clone(),
+            session: config.session()?.to_string(),
+            cache,
+            agent: agent(),
+            min_interval: Self::MIN_INTERVAL,
+        })
+    }
+
+    // Wait until `min_interval` after the last request, and record the one about to be made
+    fn wait_turn(&self) {
+        let path = self.cache.root.join("input/.last_request");
+        let last = fs::read_to_string(&path)
+            .ok()
+            .and_then(|nanos| nanos.trim().parse().ok())
+            .map(|nanos| UNIX_EPOCH + Duration::from_nanos(nanos));
+        if let Some(elapsed) = last.and_then(|last| last.elapsed().ok()) {
+            thread::sleep(self.min_interval.saturating_sub(elapsed));
+        }
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        // In nanoseconds, as rounding it down would cut the wait short. Failing to record it only
+        // risks the next run's request being too soon
+        let _ = fs::create_dir_all(path.parent().unwrap())
+            .and_then(|()| fs::write(&path, now.as_nanos().to_string()));
+    }
+
+    fn fetch(&self, year: u32, day: u32) -> io::Result<String> {
+        self.wait_turn();
+
+        let url = format!("{}/{year}/day/{day}/input", self.base_url);
+        let response = self
+            .agent
+            .get(&url)
+            .set("Cookie", &format!("session={}", self.session))
+            .call();
+        match response {
+            Ok(response) => response.into_string(),
+            Err(ureq::Error::Status(404, _)) => Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("{url}: not found, has day {day} of {year} started yet?"),
+            )),
+            Err(ureq::Error::Status(status, _)) => Err(io::Error::other(format!(
+                "{url}: HTTP {status}, is the session token still valid?"
+            ))),
+            Err(ureq::Error::Transport(e)) => Err(io::Error::other(format!("{url}: {e}"))),
+        }
+    }
+}
+
+impl InputProvider for HttpFetcher {
+    fn input(&self, year: u32, day: u32) -> io::Result<String> {
+        let input = self.fetch(year, day)?;
+        self.cache.store(year, day, &input)?;
+        eprintln!("Fetched {}", self.cache.path(year, day).display());
+        Ok(input::normalize(&input).0.into_owned())
+    }
+}
+
+/// Tries the first provider, then the second if the first doesn't have the input
+pub struct Fallback<A, B>(pub A, pub B);
+
+impl<A: InputProvider, B: InputProvider> InputProvider for Fallback<A, B> {
+    fn input(&self, year: u32, day: u32) -> io::Result<String> {
+        match self.0.input(year, day) {
+            Err(e) if e.kind() == io::ErrorKind::NotFound => self.1.input(year, day),
+            result => result,
+        }
+    }
+}
+
+/// Fetches inputs with an [`HttpFetcher`], only loading the [`Config`] once an input is needed
+///
+/// So a broken session file only matters when something has to be fetched.
+struct LazyFetcher {
+    cache: LocalCache,
+    fetcher: OnceCell<io::Result<HttpFetcher>>,
+}
+
+impl InputProvider for LazyFetcher {
+    fn input(&self, year: u32, day: u32) -> io::Result<String> {
+        let fetcher = self.fetcher.get_or_init(|| {
+            let config = Config::load()?;
+            HttpFetcher::new(&config, self.cache.clone())
+        });
+        match fetcher {
+            Ok(fetcher) => fetcher.input(year, day),
+            // Without a session token, the input is missing
+            Err(e) => Err(io::Error::new(
+                e.kind(),
+                format!(
+                    "{} is missing, and unable to fetch it: {e}",
+                    self.cache.path(year, day).display()
+                ),
+            )),
+        }
+    }
+}
+
+/// The local cache under `root`, falling back to fetching inputs if there's a session token
+pub fn default_provider(root: &Path) -> Box<dyn InputProvider> {
+    let cache = LocalCache::new(root);
+    Box::new(Fallback(
+        cache.clone(),
+        LazyFetcher {
+            cache,
+            fetcher: OnceCell::new(),
+        },
+    ))
+}
+
+/// The agent used for all requests to the puzzle server
+pub(crate) fn agent() -> ureq::Agent {
+    ureq::AgentBuilder::new()
+        .timeout(Duration::from_secs(30))
+        .user_agent(concat!("aoc_2022/", env!("CARGO_PKG_VERSION")))
+        .build()
+}
+
+/// A stand-in for the puzzle server, which answers each connection with the next of `responses`
+/// (a status and body), then stops, returning the requests it received
+#[cfg(test)]
+pub(crate) fn serve(
+    responses: Vec<(u16, &'static str)>,
+) -> (String, thread::JoinHandle<Vec<String>>) {
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+    let handle = thread::spawn(move || {
+        let mut requests = Vec::new();
+        for (status, body) in responses {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut request = String::new();
+            let mut content_length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if let Some((name, value)) = line.split_once(':') {
+                    if name.eq_ignore_ascii_case("content-length") {
+                        content_length = value.trim().parse().unwrap();
+                    }
+                }
+                if line.trim_end().is_empty() {
+                    break;
+                }
+                request.push_str(&line);
+            }
+            let mut body_in = vec![0; content_length];
+            reader.read_exact(&mut body_in).unwrap();
+            request.push_str(&String::from_utf8(body_in).unwrap());
+            requests.push(request);
+
+            write!(
+                reader.get_mut(),
+                "HTTP/1.1 {status} Stand-in\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            )
+            .unwrap();
+        }
+        requests
+    });
+    (base_url, handle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    #[test]
+    fn fetch_and_cache() {
+        let root = std::env::temp_dir().join(format!("aoc_2022_fetch_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        let (base_url, server) = serve(vec![(200, "1\r\n2\r\n"), (404, ""), (500, "")]);
+        let config = Config::new(format!("{base_url}/"), Some("abc\n".to_string()));
+
+        let cache = LocalCache::new(&root);
+        let mut fetcher = HttpFetcher::new(&config, cache.clone()).unwrap();
+        fetcher.min_interval = Duration::from_millis(50);
+        let provider = Fallback(cache.clone(), fetcher);
+
+        // An empty placeholder, like `new` creates, is fetched as if it were missing
+        cache.store(2022, 1, "").unwrap();
+        let start = Instant::now();
+        assert_eq!(provider.input(2022, 1).unwrap(), "1\n2\n");
+        assert_eq!(
+            fs::read_to_string(cache.path(2022, 1)).unwrap(),
+            "1\r\n2\r\n"
+        );
+        // Now cached, so not fetched again
+        assert_eq!(provider.input(2022, 1).unwrap(), "1\n2\n");
+
+        let err = provider.input(2022, 2).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+        // Rate limited
+        assert!(start.elapsed() >= Duration::from_millis(50));
+
+        // Including across runs, e.g. by another fetcher
+        let start = Instant::now();
+        let mut fetcher = HttpFetcher::new(&config, cache.clone()).unwrap();
+        fetcher.min_interval = Duration::from_millis(50);
+        let provider = Fallback(cache.clone(), fetcher);
+        let err = provider.input(2022, 3).unwrap_err();
+        assert!(start.elapsed() >= Duration::from_millis(40));
+        assert!(err.to_string().contains("HTTP 500"), "{err}");
+        assert!(!cache.path(2022, 3).exists());
+
+        let requests = server.join().unwrap();
+        assert_eq!(requests.len(), 3);
+        assert!(requests[0].starts_with("GET /2022/day/1/input HTTP/1.1\r\n"));
+        assert!(requests[0].contains("\r\nCookie: session=abc\r\n"));
+
+        assert!(HttpFetcher::new(&Config::new(base_url, None), cache).is_err());
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,250 @@
+use crate::{DayResult, PartResult};
+use std::io::{self, Write};
+use std::time::Duration;
+
+/// Machine readable formats for a set of day results
+#[derive(Debug, Copy, Clone, PartialEq, Eq, clap::ValueEnum)]
+pub enum Format {
+    /// A single JSON object, with timings in nanoseconds
+    Json,
+    /// One row per day, with timings in nanoseconds, and errors in their own columns
+    Csv,
+    /// A table of answers and timings in nanoseconds, suitable for a README
+    Markdown,
+}
+
+/// Write `results` in the given format, followed by `total` as the overall time taken
+pub fn write_report<W: Write>(
+    w: &mut W,
+    format: Format,
+    results: &[DayResult],
+    total: Duration,
+) -> io::Result<()> {
+    match format {
+        Format::Json => write_json(w, results, total),
+        Format::Csv => write_csv(w, results, total),
+        Format::Markdown => write_markdown(w, results, total),
+    }
+}
+
+fn write_json<W: Write>(w: &mut W, results: &[DayResult], total: Duration) -> io::Result<()> {
+    fn part_json(part: &Option<PartResult>) -> String {
+        let Some(part) = part else {
+            return "null".to_string();
+        };
+        let (key, value) = match &part.answer {
+            Ok(answer) => ("answer", answer),
+            Err(e) => ("error", e),
+        };
+        format!(
+            r#"{{"ns": {}, "{key}": {}}}"#,
+            part.elapsed.as_nanos(),
+            json_string(value)
+        )
+    }
+
+    writeln!(w, "{{")?;
+    writeln!(w, r#"  "days": ["#)?;
+    for (i, result) in results.iter().enumerate() {
+        let gen_error = result
+            .gen_error
+            .as_ref()
+            .map_or_else(|| "null".to_string(), |e| json_string(&e.to_string()));
+        write!(
+            w,
+            r#"    {{"day": {}, "generator_ns": {}, "generator_error": {gen_error}, "part_1": {}, "part_2": {}, "total_ns": {}}}"#,
+            result.day,
+            result.gen_elapsed.as_nanos(),
+            part_json(&result.part_1),
+            part_json(&result.part_2),
+            result.total_elapsed().as_nanos(),
+        )?;
+        writeln!(w, "{}", if i + 1 == results.len() { "" } else { "," })?;
+    }
+    writeln!(w, "  ],")?;
+    writeln!(w, r#"  "total_ns": {}"#, total.as_nanos())?;
+    writeln!(w, "}}")
+}
+
+fn write_csv<W: Write>(w: &mut W, results: &[DayResult], total: Duration) -> io::Result<()> {
+    // The time taken, answer and error columns of a part, only one of the last two filled in
+    fn part_csv(part: &Option<PartResult>) -> String {
+        let Some(part) = part else {
+            return ",,".to_string();
+        };
+        let (answer, e) = match &part.answer {
+            Ok(answer) => (csv_field(answer), String::new()),
+            Err(e) => (String::new(), csv_field(e)),
+        };
+        format!("{},{answer},{e}", part.elapsed.as_nanos())
+    }
+
+    writeln!(
+        w,
+        "day,generator_ns,generator_error,part_1_ns,part_1,part_1_error,part_2_ns,part_2,part_2_error,total_ns"
+    )?;
+    for result in results {
+        let gen_error = result
+            .gen_error
+            .as_ref()
+            .map_or_else(String::new, |e| csv_field(&e.to_string()));
+        writeln!(
+            w,
+            "{},{},{gen_error},{},{},{}",
+            result.day,
+            result.gen_elapsed.as_nanos(),
+            part_csv(&result.part_1),
+            part_csv(&result.part_2),
+            result.total_elapsed().as_nanos()
+        )?;
+    }
+    writeln!(w, "total,,,,,,,,,{}", total.as_nanos())
+}
+
+fn write_markdown<W: Write>(w: &mut W, results: &[DayResult], total: Duration) -> io::Result<()> {
+    // The time taken and answer cells of a part
+    fn part_cells(part: &Option<PartResult>) -> String {
+        let Some(part) = part else {
+            return "- | -".to_string();
+        };
+        let answer = match &part.answer {
+            Ok(answer) => markdown_cell(answer),
+            Err(e) => format!("error: {}", markdown_cell(e)),
+        };
+        format!("{} | {answer}", part.elapsed.as_nanos())
+    }
+
+    writeln!(
+        w,
+        "| Day | Generator (ns) | Part 1 (ns) | Part 1 | Part 2 (ns) | Part 2 | Total (ns) |"
+    )?;
+    writeln!(
+        w,
+        "|----:|---------------:|------------:|-------:|------------:|-------:|-----------:|"
+    )?;
+    for result in results {
+        let generator = match &result.gen_error {
+            Some(e) => format!("error: {}", markdown_cell(&e.to_string())),
+            None => result.gen_elapsed.as_nanos().to_string(),
+        };
+        writeln!(
+            w,
+            "| {} | {generator} | {} | {} | {} |",
+            result.day,
+            part_cells(&result.part_1),
+            part_cells(&result.part_2),
+            result.total_elapsed().as_nanos()
+        )?;
+    }
+    writeln!(w, "| **Total** | | | | | | **{}** |", total.as_nanos())
+}
+
+fn json_string(s: &str) -> String {
+    let mut result = String::with_capacity(s.len() + 2);
+    result.push('"');
+    for ch in s.chars() {
+        match ch {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            '\t' => result.push_str("\\t"),
+            ch if ch.is_control() => result.push_str(&format!("\\u{:04x}", ch as u32)),
+            ch => result.push(ch),
+        }
+    }
+    result.push('"');
+    result
+}
+
+// Multi-line answers (like day 10's) are kept on one row with `<br>`
+fn markdown_cell(s: &str) -> String {
+    s.replace('|', "\\|").replace('\n', "<br>")
+}
+
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn results() -> Vec<DayResult> {
+        vec![
+            DayResult {
+                day: 1,
+                gen_elapsed: Duration::from_nanos(1500),
+                gen_error: None,
+                part_1: Some(PartResult {
+                    elapsed: Duration::from_nanos(20),
+                    answer: Ok("24000".to_string()),
+                }),
+                part_2: Some(PartResult {
+                    elapsed: Duration::from_nanos(30),
+                    answer: Err("panic'd: \"oops\"".to_string()),
+                }),
+            },
+            DayResult {
+                day: 10,
+                gen_elapsed: Duration::from_nanos(7),
+                gen_error: None,
+                part_1: None,
+                part_2: Some(PartResult {
+                    elapsed: Duration::from_nanos(3),
+                    answer: Ok("\n##..\n..##".to_string()),
+                }),
+            },
+        ]
+    }
+
+    fn report(format: Format) -> String {
+        let mut out = Vec::new();
+        write_report(&mut out, format, &results(), Duration::from_nanos(2000)).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn json() {
+        assert_eq!(
+            report(Format::Json),
+            r#"{
+  "days": [
+    {"day": 1, "generator_ns": 1500, "generator_error": null, "part_1": {"ns": 20, "answer": "24000"}, "part_2": {"ns": 30, "error": "panic'd: \"oops\""}, "total_ns": 1550},
+    {"day": 10, "generator_ns": 7, "generator_error": null, "part_1": null, "part_2": {"ns": 3, "answer": "\n##..\n..##"}, "total_ns": 10}
+  ],
+  "total_ns": 2000
+}
+"#
+        );
+    }
+
+    #[test]
+    fn csv() {
+        assert_eq!(
+            report(Format::Csv),
+            "day,generator_ns,generator_error,part_1_ns,part_1,part_1_error,part_2_ns,part_2,part_2_error,total_ns
+1,1500,,20,24000,,30,,\"panic'd: \"\"oops\"\"\",1550
+10,7,,,,,3,\"\n##..\n..##\",,10
+total,,,,,,,,,2000
+"
+        );
+    }
+
+    #[test]
+    fn markdown() {
+        assert_eq!(
+            report(Format::Markdown),
+            "| Day | Generator (ns) | Part 1 (ns) | Part 1 | Part 2 (ns) | Part 2 | Total (ns) |
+|----:|---------------:|------------:|-------:|------------:|-------:|-----------:|
+| 1 | 1500 | 20 | 24000 | 30 | error: panic'd: \"oops\" | 1550 |
+| 10 | 7 | - | - | 3 | <br>##..<br>..## | 10 |
+| **Total** | | | | | | **2000** |
+"
+        );
+    }
+}
@@ -0,0 +1,150 @@
+use crate::{input, ROOT_YEAR};
+use std::fs::{self, OpenOptions};
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// The module for a new day, `{day_test}` is replaced with the path to `day_test!`
+const TEMPLATE: &str = r#"use crate::parse::{ParseError, Parser};
+use crate::Solution;
+
+pub struct Day;
+
+impl Solution for Day {
+    type Input<'a> = Vec<u32>;
+    type Answer1 = u32;
+    type Answer2 = u32;
+
+    fn parse(s: &str) -> Result<Self::Input<'_>, ParseError> {
+        let p = Parser::new(s);
+        s.lines().map(|line| p.number(line)).collect()
+    }
+
+    fn part_1(_input: &Self::Input<'_>) -> u32 {
+        // Placeholder, until solved
+        0
+    }
+
+    fn part_2(_input: &Self::Input<'_>) -> u32 {
+        0
+    }
+}
+
+{day_test}! {demo_1}
+{day_test}! {part_1}
+{day_test}! {demo_2}
+{day_test}! {part_2}
+"#;
+
+/// The path of the module for `day` of `year`, relative to the crate root
+pub fn module_path(year: u32, day: u32) -> PathBuf {
+    let dir = if year == ROOT_YEAR {
+        PathBuf::from("src")
+    } else {
+        Path::new("src").join(format!("y{year}"))
+    };
+    dir.join(format!("day{day}.rs"))
+}
+
+/// Create the module for a new day, and empty demo and real inputs for it (unless they already
+/// exist), under `root`, returning the paths created
+///
+/// Fails without changing anything if the day's module already exists. The build script picks
+/// up the new module, so there's nothing to register by hand, and ignores the day's tests until
+/// its inputs are filled in. An empty real input is still fetched on the first run.
+pub fn new_day(root: &Path, year: u32, day: u32) -> io::Result<Vec<PathBuf>> {
+    if !(1..=25).contains(&day) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{day} isn't a day of advent, expected 1 to 25"),
+        ));
+    }
+    let module = root.join(module_path(year, day));
+    if module.exists() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("day {day} of {year} already exists at {}", module.display()),
+        ));
+    }
+
+    let day_test = if year == ROOT_YEAR {
+        "super::day_test"
+    } else {
+        "crate::day_test"
+    };
+    fs::create_dir_all(module.parent().unwrap())?;
+    write_new(&module, &TEMPLATE.replace("{day_test}", day_test))?;
+    let mut created = vec![module];
+
+    for demo in [true, false] {
+        let input = root.join(input::day_path(year, day, demo));
+        fs::create_dir_all(input.parent().unwrap())?;
+        match write_new(&input, "") {
+            Ok(()) => created.push(input),
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(created)
+}
+
+// Write `contents` to a new file at `path`, failing if it exists
+fn write_new(path: &Path, contents: &str) -> io::Result<()> {
+    use std::io::Write;
+
+    OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(path)?
+        .write_all(contents.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scaffold_days() {
+        let root = std::env::temp_dir().join(format!("aoc_2022_scaffold_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+
+        let created = new_day(&root, 2022, 3).unwrap();
+        assert_eq!(
+            created,
+            [
+                root.join("src/day3.rs"),
+                root.join("input/2022/demo3.txt"),
+                root.join("input/2022/day3.txt"),
+            ]
+        );
+        let module = fs::read_to_string(&created[0]).unwrap();
+        assert!(module.contains("super::day_test! {demo_1}"));
+        assert_eq!(fs::read_to_string(&created[2]).unwrap(), "");
+
+        // An existing day is left alone
+        fs::write(&created[0], "solved").unwrap();
+        let err = new_day(&root, 2022, 3).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::AlreadyExists);
+        assert_eq!(fs::read_to_string(&created[0]).unwrap(), "solved");
+
+        // Inputs which already exist are kept
+        fs::create_dir_all(root.join("input/2023")).unwrap();
+        fs::write(root.join("input/2023/demo1.txt"), "input").unwrap();
+        let created = new_day(&root, 2023, 1).unwrap();
+        assert_eq!(
+            created,
+            [
+                root.join("src/y2023/day1.rs"),
+                root.join("input/2023/day1.txt")
+            ]
+        );
+        let module = fs::read_to_string(&created[0]).unwrap();
+        assert!(module.contains("crate::day_test! {part_2}"));
+        assert_eq!(
+            fs::read_to_string(root.join("input/2023/demo1.txt")).unwrap(),
+            "input"
+        );
+
+        assert!(new_day(&root, 2022, 26).is_err());
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
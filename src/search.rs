@@ -0,0 +1,269 @@
+//! Shortest paths over graphs given by a successor function
+//!
+//! Every search can start from several nodes at once, and stops as soon as it reaches a node
+//! matching `is_goal` (pass `|_| false` to explore everything reachable). The returned [`Search`]
+//! holds the cost of every node reached, and how it was reached, so any [`Path`] can be
+//! reconstructed afterwards.
+
+use ahash::{HashMap, HashMapExt};
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, VecDeque};
+use std::hash::Hash;
+use std::ops::Add;
+
+/// How a node was reached
+#[derive(Debug, Copy, Clone)]
+struct Visit<N, C> {
+    cost: C,
+    /// `None` for the starts
+    parent: Option<N>,
+}
+
+/// The outcome of a search
+#[derive(Debug, Clone)]
+pub struct Search<N, C> {
+    visited: HashMap<N, Visit<N, C>>,
+    goal: Option<N>,
+}
+
+/// The nodes from a start to some node, both included
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Path<N, C> {
+    pub nodes: Vec<N>,
+    pub cost: C,
+}
+
+impl<N: Copy, C> Path<N, C> {
+    pub fn start(&self) -> N {
+        self.nodes[0]
+    }
+
+    pub fn end(&self) -> N {
+        self.nodes[self.nodes.len() - 1]
+    }
+}
+
+impl<N: Copy + Eq + Hash, C: Copy> Search<N, C> {
+    fn new(capacity: usize) -> Self {
+        Self {
+            visited: HashMap::with_capacity(capacity),
+            goal: None,
+        }
+    }
+
+    /// The goal the search stopped at, `None` if no goal was reachable
+    pub fn goal(&self) -> Option<N> {
+        self.goal
+    }
+
+    /// The cost of the cheapest path to the goal
+    pub fn goal_cost(&self) -> Option<C> {
+        self.cost(self.goal?)
+    }
+
+    /// The cost of the cheapest path found to `node`
+    ///
+    /// Only final for nodes the search got to before stopping, and for the goal.
+    pub fn cost(&self, node: N) -> Option<C> {
+        Some(self.visited.get(&node)?.cost)
+    }
+
+    /// Every node reached, with its cost
+    pub fn costs(&self) -> impl Iterator<Item = (N, C)> + '_ {
+        self.visited.iter().map(|(&node, visit)| (node, visit.cost))
+    }
+
+    /// The cheapest path to the goal
+    pub fn path(&self) -> Option<Path<N, C>> {
+        self.path_to(self.goal?)
+    }
+
+    /// The cheapest path found to `node`, from whichever start it's cheapest from
+    pub fn path_to(&self, node: N) -> Option<Path<N, C>> {
+        let cost = self.cost(node)?;
+        let mut nodes = vec![node];
+        let mut current = node;
+        while let Some(parent) = self.visited[&current].parent {
+            nodes.push(parent);
+            current = parent;
+        }
+        nodes.reverse();
+        Some(Path { nodes, cost })
+    }
+}
+
+/// Breadth first search, where every step costs 1
+pub fn bfs<N, I>(
+    starts: impl IntoIterator<Item = N>,
+    mut successors: impl FnMut(N) -> I,
+    mut is_goal: impl FnMut(N) -> bool,
+) -> Search<N, usize>
+where
+    N: Copy + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut search = Search::new(1024);
+    let mut queue = VecDeque::with_capacity(256);
+    for start in starts {
+        if let Entry::Vacant(e) = search.visited.entry(start) {
+            e.insert(Visit {
+                cost: 0,
+                parent: None,
+            });
+            queue.push_back((start, 0));
+        }
+    }
+
+    while let Some((current, cost)) = queue.pop_front() {
+        if is_goal(current) {
+            search.goal = Some(current);
+            break;
+        }
+        for next in successors(current) {
+            if let Entry::Vacant(e) = search.visited.entry(next) {
+                e.insert(Visit {
+                    cost: cost + 1,
+                    parent: Some(current),
+                });
+                queue.push_back((next, cost + 1));
+            }
+        }
+    }
+    search
+}
+
+/// Dijkstra's algorithm, `successors` gives each next node with the cost of the step to it
+pub fn dijkstra<N, C, I>(
+    starts: impl IntoIterator<Item = N>,
+    successors: impl FnMut(N) -> I,
+    is_goal: impl FnMut(N) -> bool,
+) -> Search<N, C>
+where
+    N: Copy + Eq + Hash,
+    C: Copy + Ord + Add<Output = C> + Default,
+    I: IntoIterator<Item = (N, C)>,
+{
+    astar(starts, successors, |_| C::default(), is_goal)
+}
+
+/// A* search, `heuristic` must never overestimate the remaining cost to a goal
+///
+/// Costs are queued in buckets, so this is fastest when there are few distinct costs, e.g. small
+/// integers.
+pub fn astar<N, C, I>(
+    starts: impl IntoIterator<Item = N>,
+    mut successors: impl FnMut(N) -> I,
+    mut heuristic: impl FnMut(N) -> C,
+    mut is_goal: impl FnMut(N) -> bool,
+) -> Search<N, C>
+where
+    N: Copy + Eq + Hash,
+    C: Copy + Ord + Add<Output = C> + Default,
+    I: IntoIterator<Item = (N, C)>,
+{
+    let mut search = Search::new(1024);
+    // Nodes with their cost so far, by estimated total cost
+    let mut frontier = BTreeMap::<C, Vec<(N, C)>>::new();
+    for start in starts {
+        let cost = C::default();
+        if let Entry::Vacant(e) = search.visited.entry(start) {
+            e.insert(Visit { cost, parent: None });
+            frontier
+                .entry(heuristic(start))
+                .or_default()
+                .push((start, cost));
+        }
+    }
+
+    // Whether any node has been queued more than once, only then can a queued cost be stale
+    let mut requeued = false;
+    while let Some((estimate, mut items)) = frontier.pop_first() {
+        while let Some((current, cost)) = items.pop() {
+            if requeued && search.visited[&current].cost < cost {
+                // Already reached more cheaply since this was queued
+                continue;
+            }
+            if is_goal(current) {
+                search.goal = Some(current);
+                return search;
+            }
+
+            let mut best_estimate = None;
+            for (next, step_cost) in successors(current) {
+                let next_cost = cost + step_cost;
+                let visit = Visit {
+                    cost: next_cost,
+                    parent: Some(current),
+                };
+                match search.visited.entry(next) {
+                    Entry::Occupied(mut e) if next_cost < e.get().cost => {
+                        e.insert(visit);
+                        requeued = true;
+                    }
+                    Entry::Occupied(_) => continue,
+                    Entry::Vacant(e) => {
+                        e.insert(visit);
+                    }
+                }
+                let next_estimate = next_cost + heuristic(next);
+                frontier
+                    .entry(next_estimate)
+                    .or_default()
+                    .push((next, next_cost));
+                best_estimate =
+                    Some(best_estimate.map_or(next_estimate, |best: C| best.min(next_estimate)));
+            }
+
+            // If something better than the rest of this bucket was found, put the rest back,
+            // and continue from the better one
+            if best_estimate.is_some_and(|best| best < estimate) && !items.is_empty() {
+                frontier.entry(estimate).or_default().append(&mut items);
+                break;
+            }
+        }
+    }
+    search
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A line of nodes 0..10, where each step right costs 2, and there's a shortcut from 2 to 7
+    fn line(n: u32) -> Vec<(u32, u32)> {
+        let mut next = vec![];
+        if n < 9 {
+            next.push((n + 1, 2));
+        }
+        if n == 2 {
+            next.push((7, 3));
+        }
+        next
+    }
+
+    #[test]
+    fn searches() {
+        let search = dijkstra([0], line, |n| n == 9);
+        assert_eq!(search.goal(), Some(9));
+        assert_eq!(search.goal_cost(), Some(11));
+        let path = search.path().unwrap();
+        assert_eq!(path.nodes, [0, 1, 2, 7, 8, 9]);
+        assert_eq!((path.start(), path.end(), path.cost), (0, 9, 11));
+
+        let search = astar([0], line, |n| 9 - n, |n| n == 9);
+        assert_eq!(search.path().unwrap().nodes, [0, 1, 2, 7, 8, 9]);
+
+        // Several starts, the cheapest path comes from the closest
+        let search = bfs([0, 5], |n| line(n).into_iter().map(|(n, _)| n), |n| n == 9);
+        assert_eq!(search.path().unwrap().nodes, [5, 6, 7, 8, 9]);
+        assert_eq!(search.goal_cost(), Some(4));
+
+        // No goal, exploring everything
+        let search = bfs([3], |n| line(n).into_iter().map(|(n, _)| n), |_| false);
+        assert_eq!(search.goal(), None);
+        assert_eq!(search.path(), None);
+        assert_eq!(search.costs().count(), 7);
+        assert_eq!(search.cost(2), None);
+        assert_eq!(search.path_to(9).unwrap().nodes, [3, 4, 5, 6, 7, 8, 9]);
+    }
+}
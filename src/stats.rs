@@ -0,0 +1,167 @@
+use crate::{DayResult, PartResult};
+use std::fmt;
+use std::time::Duration;
+
+/// Summary of the time taken by a phase over repeated runs
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub std_dev: Duration,
+}
+
+impl Stats {
+    /// Summarize `samples`, or `None` if there aren't any
+    pub fn new(samples: &[Duration]) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+        let mut sorted = samples.to_vec();
+        sorted.sort_unstable();
+        let mid = sorted.len() / 2;
+        let median = if sorted.len().is_multiple_of(2) {
+            (sorted[mid - 1] + sorted[mid]) / 2
+        } else {
+            sorted[mid]
+        };
+
+        let n = samples.len() as f64;
+        let mean = samples.iter().map(|d| d.as_nanos() as f64).sum::<f64>() / n;
+        let variance = samples
+            .iter()
+            .map(|d| (d.as_nanos() as f64 - mean).powi(2))
+            .sum::<f64>()
+            / n;
+
+        Some(Self {
+            min: sorted[0],
+            median,
+            mean: Duration::from_nanos(mean.round() as u64),
+            std_dev: Duration::from_nanos(variance.sqrt().round() as u64),
+        })
+    }
+}
+
+/// Shown as e.g. `median 1.23ms ±0.04, min 1.20ms, mean 1.24ms`, with the standard deviation in
+/// the same unit as the median
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (scale, unit) = unit_for(self.median);
+        let in_unit = |d: Duration| d.as_secs_f64() / scale;
+        let (min_scale, min_unit) = unit_for(self.min);
+        let (mean_scale, mean_unit) = unit_for(self.mean);
+        write!(
+            f,
+            "median {:.2}{unit} ±{:.2}, min {:.2}{min_unit}, mean {:.2}{mean_unit}",
+            in_unit(self.median),
+            in_unit(self.std_dev),
+            self.min.as_secs_f64() / min_scale,
+            self.mean.as_secs_f64() / mean_scale,
+        )
+    }
+}
+
+// The same units `Duration`'s `Debug` impl picks
+fn unit_for(d: Duration) -> (f64, &'static str) {
+    match d.as_nanos() {
+        0..=999 => (1e-9, "ns"),
+        1_000..=999_999 => (1e-6, "µs"),
+        1_000_000..=999_999_999 => (1e-3, "ms"),
+        _ => (1.0, "s"),
+    }
+}
+
+/// Timings of each phase of a day over repeated runs
+#[derive(Debug, Clone)]
+pub struct RepeatedResult {
+    /// The last run, for its answers
+    pub last: DayResult,
+    pub runs: usize,
+    pub generator: Stats,
+    /// `None` for parts which never ran
+    pub part_1: Option<Stats>,
+    pub part_2: Option<Stats>,
+    pub total: Stats,
+}
+
+impl RepeatedResult {
+    /// Summarize repeated runs of the same day, `None` if there weren't any runs
+    pub fn new(results: Vec<DayResult>) -> Option<Self> {
+        let part_stats = |part: fn(&DayResult) -> &Option<PartResult>| {
+            let samples: Vec<_> = results
+                .iter()
+                .filter_map(|r| part(r).as_ref().map(|p| p.elapsed))
+                .collect();
+            Stats::new(&samples)
+        };
+        let generator = Stats::new(&results.iter().map(|r| r.gen_elapsed).collect::<Vec<_>>())?;
+        let part_1 = part_stats(|r| &r.part_1);
+        let part_2 = part_stats(|r| &r.part_2);
+        let total = Stats::new(
+            &results
+                .iter()
+                .map(DayResult::total_elapsed)
+                .collect::<Vec<_>>(),
+        )?;
+
+        let runs = results.len();
+        Some(Self {
+            last: results.into_iter().last()?,
+            runs,
+            generator,
+            part_1,
+            part_2,
+            total,
+        })
+    }
+}
+
+impl fmt::Display for RepeatedResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(e) = &self.last.gen_error {
+            return writeln!(f, "Generator error: {e}");
+        }
+        writeln!(f, "Gen    ({})", self.generator)?;
+        let parts = [
+            (&self.last.part_1, &self.part_1),
+            (&self.last.part_2, &self.part_2),
+        ];
+        for (i, (part, stats)) in parts.into_iter().enumerate() {
+            if let (Some(part), Some(stats)) = (part, stats) {
+                let answer = match &part.answer {
+                    Ok(answer) | Err(answer) => answer,
+                };
+                writeln!(f, "Part {} ({stats}) {answer}", i + 1)?;
+            }
+        }
+        writeln!(f, "Total  ({})", self.total)?;
+        writeln!(f, "({} runs)", self.runs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stats() {
+        let ms = Duration::from_millis;
+        let stats = Stats::new(&[ms(4), ms(2), ms(9), ms(5), ms(4), ms(5), ms(7), ms(4)]).unwrap();
+        assert_eq!(stats.min, ms(2));
+        assert_eq!(stats.median, ms(4) + Duration::from_micros(500));
+        assert_eq!(stats.mean, ms(5));
+        assert_eq!(stats.std_dev, ms(2));
+        assert_eq!(
+            stats.to_string(),
+            "median 4.50ms ±2.00, min 2.00ms, mean 5.00ms"
+        );
+
+        let stats = Stats::new(&[Duration::from_nanos(1_230_000)]).unwrap();
+        assert_eq!(
+            stats.to_string(),
+            "median 1.23ms ±0.00, min 1.23ms, mean 1.23ms"
+        );
+        assert_eq!(Stats::new(&[]), None);
+    }
+}
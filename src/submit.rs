@@ -0,0 +1,353 @@
+use crate::config::Config;
+use crate::isolate::{escape, unescape};
+use crate::provider;
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// The server's response to a submitted answer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Right,
+    Wrong,
+    TooHigh,
+    TooLow,
+    /// An answer was submitted too recently, with how long is left to wait, if given
+    RateLimited(Option<Duration>),
+    /// The part was already solved (or part 1 isn't solved yet, for part 2)
+    WrongLevel,
+    /// A response which wasn't understood, holds the text of the response
+    Unknown(String),
+}
+
+impl Outcome {
+    /// Parse the HTML page returned after submitting an answer
+    pub fn parse(html: &str) -> Self {
+        let text = article_text(html);
+        if text.contains("That's the right answer") {
+            Self::Right
+        } else if text.contains("That's not the right answer") {
+            if text.contains("your answer is too high") {
+                Self::TooHigh
+            } else if text.contains("your answer is too low") {
+                Self::TooLow
+            } else {
+                Self::Wrong
+            }
+        } else if text.contains("You gave an answer too recently") {
+            Self::RateLimited(parse_wait(&text))
+        } else if text.contains("You don't seem to be solving the right level") {
+            Self::WrongLevel
+        } else {
+            Self::Unknown(text)
+        }
+    }
+
+    /// The name an answer is recorded with in [`Guesses`], for outcomes which reject the answer
+    fn rejection_name(&self) -> Option<&'static str> {
+        match self {
+            Self::Wrong => Some("wrong"),
+            Self::TooHigh => Some("too_high"),
+            Self::TooLow => Some("too_low"),
+            _ => None,
+        }
+    }
+
+    fn from_rejection_name(name: &str) -> Option<Self> {
+        [Self::Wrong, Self::TooHigh, Self::TooLow]
+            .into_iter()
+            .find(|outcome| outcome.rejection_name() == Some(name))
+    }
+
+    /// True if the answer was definitely wrong, so shouldn't be submitted again
+    pub fn is_rejection(&self) -> bool {
+        self.rejection_name().is_some()
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Right => f.write_str("That's the right answer!"),
+            Self::Wrong => f.write_str("That's not the right answer"),
+            Self::TooHigh => f.write_str("That's not the right answer, it's too high"),
+            Self::TooLow => f.write_str("That's not the right answer, it's too low"),
+            Self::RateLimited(Some(wait)) => {
+                write!(f, "Answered too recently, try again in {wait:?}")
+            }
+            Self::RateLimited(None) => f.write_str("Answered too recently, try again later"),
+            Self::WrongLevel => f.write_str("Not solving the right level, already solved?"),
+            Self::Unknown(text) => write!(f, "Unexpected response: {text}"),
+        }
+    }
+}
+
+// The text of the `<article>` in `html` (or all of it, if there isn't one), without tags
+fn article_text(html: &str) -> String {
+    // The article starts just inside its opening tag
+    let (article, mut in_tag) = match html
+        .split_once("<article")
+        .and_then(|(_, rest)| rest.split_once("</article>"))
+    {
+        Some((article, _)) => (article, true),
+        None => (html, false),
+    };
+    let mut text = String::with_capacity(article.len());
+    for ch in article.chars() {
+        match ch {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            _ if !in_tag => text.push(ch),
+            _ => {}
+        }
+    }
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+// The wait in e.g. "You have 1m 23s left to wait"
+fn parse_wait(text: &str) -> Option<Duration> {
+    let (_, rest) = text.split_once("You have ")?;
+    let (wait, _) = rest.split_once(" left to wait")?;
+    let mut seconds = 0;
+    for part in wait.split_whitespace() {
+        let (value, multiplier) = if let Some(m) = part.strip_suffix('m') {
+            (m, 60)
+        } else {
+            (part.strip_suffix('s')?, 1)
+        };
+        seconds += value.parse::<u64>().ok()? * multiplier;
+    }
+    Some(Duration::from_secs(seconds))
+}
+
+/// Posts answers to [`Config::base_url`]
+pub struct Submitter {
+    base_url: String,
+    session: String,
+    agent: ureq::Agent,
+}
+
+impl Submitter {
+    /// Fails if `config` has no session token
+    pub fn new(config: &Config) -> io::Result<Self> {
+        Ok(Self {
+            base_url: config.base_url.clone(),
+            session: config.session()?.to_string(),
+            agent: provider::agent(),
+        })
+    }
+
+    pub fn submit(&self, year: u32, day: u32, part: u8, answer: &str) -> io::Result<Outcome> {
+        let url = format!("{}/{year}/day/{day}/answer", self.base_url);
+        let response = self
+            .agent
+            .post(&url)
+            .set("Cookie", &format!("session={}", self.session))
+            .send_form(&[("level", &part.to_string()), ("answer", answer)]);
+        match response {
+            Ok(response) => Ok(Outcome::parse(&response.into_string()?)),
+            Err(ureq::Error::Status(status, _)) => Err(io::Error::other(format!(
+                "{url}: HTTP {status}, is the session token still valid?"
+            ))),
+            Err(ureq::Error::Transport(e)) => Err(io::Error::other(format!("{url}: {e}"))),
+        }
+    }
+}
+
+/// An answer the server rejected
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Guess {
+    pub day: u32,
+    pub part: u8,
+    pub outcome: Outcome,
+    pub answer: String,
+}
+
+/// The history of rejected answers for a year, saved in `input/<year>/guesses.txt`
+///
+/// Each line is `<day> <part> <wrong|too_high|too_low> <answer>`, with newlines in the answer
+/// escaped.
+#[derive(Debug, Clone)]
+pub struct Guesses {
+    path: PathBuf,
+    guesses: Vec<Guess>,
+}
+
+impl Guesses {
+    /// Load the guesses at `path`, none if it doesn't exist yet
+    pub fn load(path: impl Into<PathBuf>) -> io::Result<Self> {
+        let path = path.into();
+        let s = match fs::read_to_string(&path) {
+            Ok(s) => s,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e),
+        };
+        let guesses = s
+            .lines()
+            .enumerate()
+            .map(|(i, line)| {
+                parse_guess(line).ok_or_else(|| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("{}:{}: invalid guess", path.display(), i + 1),
+                    )
+                })
+            })
+            .collect::<io::Result<_>>()?;
+        Ok(Self { path, guesses })
+    }
+
+    pub fn guesses(&self) -> &[Guess] {
+        &self.guesses
+    }
+
+    /// Why `answer` is known to be wrong, if it is
+    ///
+    /// As well as answers which were rejected, numbers at least as high as an answer which was too
+    /// high (or as low as one too low) are known to be wrong.
+    pub fn known_wrong(&self, day: u32, part: u8, answer: &str) -> Option<String> {
+        let number = answer.parse::<i128>().ok();
+        self.guesses
+            .iter()
+            .filter(|g| g.day == day && g.part == part)
+            .find_map(|g| {
+                let guess = g.answer.parse::<i128>().ok();
+                let name = g.outcome.rejection_name().unwrap();
+                match (&g.outcome, number, guess) {
+                    _ if g.answer == answer => {
+                        Some(format!("{answer} was already rejected ({name})"))
+                    }
+                    (Outcome::TooHigh, Some(n), Some(guess)) if n >= guess => {
+                        Some(format!("{guess} was already too high"))
+                    }
+                    (Outcome::TooLow, Some(n), Some(guess)) if n <= guess => {
+                        Some(format!("{guess} was already too low"))
+                    }
+                    _ => None,
+                }
+            })
+    }
+
+    /// Record that `answer` was rejected with `outcome`, does nothing for other outcomes
+    pub fn record(
+        &mut self,
+        day: u32,
+        part: u8,
+        outcome: &Outcome,
+        answer: &str,
+    ) -> io::Result<()> {
+        let Some(name) = outcome.rejection_name() else {
+            return Ok(());
+        };
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(file, "{day} {part} {name} {}", escape(answer))?;
+        self.guesses.push(Guess {
+            day,
+            part,
+            outcome: outcome.clone(),
+            answer: answer.to_string(),
+        });
+        Ok(())
+    }
+}
+
+fn parse_guess(line: &str) -> Option<Guess> {
+    let mut fields = line.splitn(4, ' ');
+    Some(Guess {
+        day: fields.next()?.parse().ok()?,
+        part: fields.next()?.parse().ok()?,
+        outcome: Outcome::from_rejection_name(fields.next()?)?,
+        answer: unescape(fields.next()?),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page(article: &str) -> String {
+        format!("<html><body><main><article><p>{article}</p></article></main></body></html>")
+    }
+
+    #[test]
+    fn parse_outcomes() {
+        let right = page("That's the right answer! You are <em>one gold star</em> closer.");
+        assert_eq!(Outcome::parse(&right), Outcome::Right);
+        let high = page("That's not the right answer; your answer is too high. Please wait.");
+        assert_eq!(Outcome::parse(&high), Outcome::TooHigh);
+        let low = page("That's not the right answer; your answer is too low.");
+        assert_eq!(Outcome::parse(&low), Outcome::TooLow);
+        let wrong = page("That's not the right answer. If you're stuck, ...");
+        assert_eq!(Outcome::parse(&wrong), Outcome::Wrong);
+        let limited = page(
+            "You gave an answer too recently; you have to wait after submitting an answer \
+            before trying again.  You have 1m 23s left to wait. <a href=\"/2022/day/1\">[Return]</a>",
+        );
+        assert_eq!(
+            Outcome::parse(&limited),
+            Outcome::RateLimited(Some(Duration::from_secs(83)))
+        );
+        let level =
+            page("You don't seem to be solving the right level.  Did you already complete it?");
+        assert_eq!(Outcome::parse(&level), Outcome::WrongLevel);
+        assert_eq!(
+            Outcome::parse(&page("Something <b>else</b>")),
+            Outcome::Unknown("Something else".to_string())
+        );
+    }
+
+    #[test]
+    fn submit_and_remember() {
+        let path = std::env::temp_dir().join(format!("aoc_2022_guesses_{}", std::process::id()));
+        let _ = fs::remove_file(&path);
+        let responses = vec![
+            (
+                200,
+                "<article><p>That's not the right answer; your answer is too high.</p></article>",
+            ),
+            (200, "<article><p>That's the right answer!</p></article>"),
+        ];
+        let (base_url, server) = provider::serve(responses);
+        let submitter = Submitter::new(&Config::new(base_url, Some("abc".to_string()))).unwrap();
+
+        let mut guesses = Guesses::load(&path).unwrap();
+        let outcome = submitter.submit(2022, 3, 2, "1000").unwrap();
+        assert_eq!(outcome, Outcome::TooHigh);
+        guesses.record(3, 2, &outcome, "1000").unwrap();
+        let outcome = submitter.submit(2022, 3, 2, "70").unwrap();
+        assert_eq!(outcome, Outcome::Right);
+        guesses.record(3, 2, &outcome, "70").unwrap();
+
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("POST /2022/day/3/answer HTTP/1.1\r\n"));
+        assert!(requests[0].contains("\r\nCookie: session=abc\r\n"));
+        assert!(requests[0].ends_with("level=2&answer=1000"));
+
+        // Reloaded from the file
+        let mut guesses = Guesses::load(&path).unwrap();
+        assert_eq!(guesses.guesses().len(), 1);
+        assert_eq!(
+            guesses.known_wrong(3, 2, "1000").as_deref(),
+            Some("1000 was already rejected (too_high)")
+        );
+        assert_eq!(
+            guesses.known_wrong(3, 2, "2000").as_deref(),
+            Some("1000 was already too high")
+        );
+        assert_eq!(guesses.known_wrong(3, 2, "999"), None);
+        assert_eq!(guesses.known_wrong(3, 1, "1000"), None);
+
+        guesses.record(10, 2, &Outcome::Wrong, "#.\n.#").unwrap();
+        let guesses = Guesses::load(&path).unwrap();
+        assert!(guesses.known_wrong(10, 2, "#.\n.#").is_some());
+        fs::remove_file(&path).unwrap();
+    }
+}